@@ -1,13 +1,54 @@
+use std::io;
+use std::sync::Arc;
+use image::{DynamicImage, GenericImage, Rgba, RgbaImage};
 use ratatui::widgets::ListState;
 use crate::cache::FileCache;
-use crate::image::ImageHandler;
-use crate::pdf::{BookMarkIndex, BookMarkType, PdfHandler, PdfSize};
+use crate::clipboard;
+use crate::image::{ImageHandler, PageImage};
+use crate::pdf::{BookMarkIndex, BookMarkType, PageLink, PdfHandler, PdfSize, ZoomMode};
+use crate::rasterizer::Rasterizer;
+use crate::index::SearchIndex;
+use crate::jump::JumpList;
+use crate::search::{build_matcher, find_matches, SearchMatch};
+use crate::text::PageText;
+use crate::emit;
 
 #[derive(Debug, Clone)]
 pub enum AppState {
     Normal,
     Search(String),
     JumpPage(String),
+    /// thumbnail overview, selected page
+    Grid(u32),
+    /// document info popup
+    Metadata,
+    /// labels drawn on the links of the visible pages, typed label so far
+    LinkHint(String),
+    /// asking the password of an encrypted pdf, typed so far
+    Password(String),
+}
+
+/// A link on a visible page and the label typed to follow it
+#[derive(Debug, Clone)]
+pub struct LinkHint {
+    pub label: String,
+    pub page: u32,
+    pub link: PageLink,
+}
+
+impl LinkHint {
+    /// Number the links with labels of the same length, `01` to `12` for twelve links.
+    pub fn label(links: Vec<(u32, PageLink)>) -> Vec<Self> {
+        let width = links.len().to_string().len();
+        links.into_iter().enumerate()
+            .map(|(index, (page, link))| LinkHint { label: format!("{:0width$}", index + 1), page, link })
+            .collect()
+    }
+
+    /// Hints still reachable once `typed` has been typed.
+    pub fn matching<'a>(hints: &'a [Self], typed: &'a str) -> impl Iterator<Item = &'a Self> {
+        hints.iter().filter(move |hint| hint.label.starts_with(typed))
+    }
 }
 
 /// Application.
@@ -39,15 +80,91 @@ pub struct App {
     pub next_load_page: u32,
     /// state
     pub app_state: AppState,
+    /// page pixel shown at the top-left corner of the preview
+    pub viewport: (u32, u32),
+    /// how pages are laid out in the preview
+    pub layout: PageLayout,
+    /// hits of the last search
+    pub search_results: Vec<SearchMatch>,
+    /// selected hit
+    pub search_state: ListState,
+    /// search with a regular expression instead of literal text
+    pub search_regex: bool,
+    /// why the last search failed
+    pub search_error: Option<String>,
+    /// full-text index, None until built in the background
+    pub search_index: Option<Arc<SearchIndex>>,
+    /// show the extracted text instead of the page image
+    pub text_mode: bool,
+    /// first line of the text shown in text mode
+    pub text_scroll: u16,
+    /// lines of the text after wrapping to the pane, as last drawn
+    pub text_lines: Vec<String>,
+    /// (page, text in reading order) last shown in text mode
+    reading_text: Option<(u32, String)>,
+    /// rows of the pane the text was drawn in
+    pub text_height: u16,
+    /// (anchor, cursor) lines of the visual selection in text mode
+    pub text_selection: Option<(usize, usize)>,
+    /// one-off message shown in the title until the next key
+    pub status: Option<String>,
+    /// links labelled while in [`AppState::LinkHint`]
+    pub link_hints: Vec<LinkHint>,
+    /// pages left by bookmark, page, search and link jumps
+    pub jump_list: JumpList,
+}
+
+/// How pages are laid out in the preview
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PageLayout {
+    /// one page at a time
+    #[default]
+    Single,
+    /// pages stitched vertically and scrolled by lines
+    Continuous,
+    /// two facing pages, 1-2, 3-4, ...
+    Spread,
+    /// like a printed book: the cover alone, then 2-3, 4-5, ...
+    Book,
 }
 
+/// Pixels between two pages in continuous and spread layouts
+const PAGE_GAP: u32 = 8;
+/// Rough thumbnail width the grid columns are derived from
+const THUMBNAIL_WIDTH: u32 = 240;
+/// Colors search hits are tinted with, out of 255 opacity
+const HIGHLIGHT: [u8; 3] = [255, 220, 0];
+const HIGHLIGHT_SELECTED: [u8; 3] = [255, 120, 0];
+const HIGHLIGHT_ALPHA: u32 = 100;
+/// 3x5 bitmaps of the digits link hints are drawn with, a row per byte
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
 
 impl App {
     /// Constructs a new instance of [`App`].
-    pub fn new(path: &str, last_page: u32) -> Self {
-        Self {
+    pub fn new(path: &str, last_page: u32, rasterizer: Arc<dyn Rasterizer>, password: Option<&str>) -> crate::error::Result<Self> {
+        let pdf_handler = PdfHandler::new(path, password)?;
+        let pdf_size = PdfSize::new(1200, 1500, 0, 0);
+        let mut page_cache = FileCache::new(path.to_string(), rasterizer, pdf_handler.get_page_sizes().clone(), pdf_size.clone())?;
+        page_cache.set_password(pdf_handler.get_password().map(str::to_string));
+        // encrypted and none of the given passwords fit
+        let app_state = if pdf_handler.is_locked() { AppState::Password(String::new()) } else { AppState::Normal };
+        // what was skipped while parsing the pdf
+        let status = (!pdf_handler.get_warnings().is_empty()).then(|| pdf_handler.get_warnings().join("; "));
+        Ok(Self {
             running: true,
-            pdf_handler: PdfHandler::new(path),
+            pdf_handler,
             image_handler: ImageHandler::new(),
             book_marks_state: ListState::default(),
             ui_book_marks: None,
@@ -55,11 +172,27 @@ impl App {
             cur_book_mark: None,
             already_render: false,
             loading: true,
-            page_cache: FileCache::new(path.to_string()),
-            pdf_size: PdfSize::new(1200, 1500, 0, 0),
+            page_cache,
+            pdf_size,
             next_load_page: 2,
-            app_state: AppState::Normal,
-        }
+            app_state,
+            viewport: (0, 0),
+            layout: PageLayout::default(),
+            search_results: vec![],
+            search_state: ListState::default(),
+            search_regex: false,
+            search_error: None,
+            search_index: None,
+            text_mode: false,
+            text_scroll: 0,
+            text_lines: vec![],
+            reading_text: None,
+            text_height: 0,
+            text_selection: None,
+            status,
+            link_hints: vec![],
+            jump_list: JumpList::default(),
+        })
     }
 
     /// Handles the tick event of the terminal.
@@ -147,15 +280,164 @@ impl App {
             if let Some(ui_book_marks) = self.ui_book_marks.as_ref() {
                 let index = &ui_book_marks[index];
                 let book_mark = self.pdf_handler.find_book_mark(index).unwrap();
-                self.cur_page = book_mark.borrow().get_num();
+                let page = book_mark.borrow().get_num();
+                self.jump_list.push(self.reading_page(), page);
+                self.cur_page = page;
+                self.viewport.1 = 0;
                 self.already_render = false;
             }
         }
     }
 
+    /// Search the document text and jump to the first hit from the current page on.
+    /// An empty query clears the results.
+    pub(crate) fn search(&mut self, query: &str) -> anyhow::Result<()> {
+        if !self.search_results.is_empty() {
+            self.search_results.clear();
+            self.search_state.select(None);
+            // 去掉页面上的高亮
+            emit!(RenderPdf);
+        }
+        if query.is_empty() {
+            return Ok(());
+        }
+        self.search_results = match self.search_index.as_ref() {
+            Some(index) => index.search(query, self.search_regex)?,
+            None => {
+                // 索引未就绪, 直接提取
+                let matcher = build_matcher(query, self.search_regex)?;
+                self.pdf_handler.get_page_texts().enumerate()
+                    .flat_map(|(index, page)| find_matches(&matcher, index as u32 + 1, &page.text))
+                    .collect()
+            }
+        };
+        let first = self.search_results.iter().position(|hit| hit.page >= self.cur_page);
+        if let Some(index) = first.or(if self.search_results.is_empty() { None } else { Some(0) }) {
+            self.select_search_result(index);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn next_search_result(&mut self) {
+        if !self.search_results.is_empty() {
+            let index = self.search_state.selected().map_or(0, |index| (index + 1) % self.search_results.len());
+            self.select_search_result(index);
+        }
+    }
+
+    pub(crate) fn previous_search_result(&mut self) {
+        if !self.search_results.is_empty() {
+            let len = self.search_results.len();
+            let index = self.search_state.selected().map_or(len - 1, |index| (index + len - 1) % len);
+            self.select_search_result(index);
+        }
+    }
+
+    fn select_search_result(&mut self, index: usize) {
+        self.search_state.select(Some(index));
+        let page = self.search_results[index].page;
+        if page != self.cur_page {
+            self.jump_list.push(self.reading_page(), page);
+            self.cur_page = page;
+            self.viewport.1 = 0;
+            self.already_render = false;
+            self.cur_book_mark = self.pdf_handler.find_book_mark_by_page_num(self.cur_page);
+        } else {
+            emit!(RenderPdf);
+        }
+    }
+
+    /// Decrypt the pdf with the typed password, then show it.
+    pub(crate) fn unlock(&mut self, password: &str) {
+        match self.pdf_handler.unlock(password) {
+            Ok(true) => {}
+            Ok(false) => {
+                self.status = Some(String::from("密码错误"));
+                emit!(ChangeState(AppState::Password(String::new())));
+                return;
+            }
+            // a broken encryption dictionary, let the password be typed again rather than quit
+            Err(e) => {
+                self.status = Some(e.to_string());
+                emit!(ChangeState(AppState::Password(String::new())));
+                return;
+            }
+        }
+        self.page_cache.set_password(self.pdf_handler.get_password().map(str::to_string));
+        self.reading_text = None;
+        self.cur_book_mark = self.pdf_handler.find_book_mark_by_page_num(self.cur_page);
+        self.already_render = false;
+        self.start_indexing();
+        emit!(ChangeState(AppState::Normal));
+    }
+
+    /// Label the links of the visible pages with numbers of equal length.
+    pub(crate) fn start_link_hints(&mut self) {
+        if self.text_mode {
+            return;
+        }
+        let links: Vec<(u32, PageLink)> = self.visible_pages().into_iter()
+            .flat_map(|page| self.pdf_handler.get_page_links(page).into_iter().map(move |link| (page, link)))
+            .collect();
+        if links.is_empty() {
+            self.status = Some(String::from("没有链接"));
+            return;
+        }
+        self.link_hints = LinkHint::label(links);
+        emit!(ChangeState(AppState::LinkHint(String::new())));
+        emit!(RenderPdf);
+    }
+
+    /// Follow the link labelled `typed`, or drop the labels not starting with it.
+    pub(crate) fn type_link_hint(&mut self, typed: &str) {
+        // labels have the same length, a complete one matches only itself
+        let matching: Vec<&LinkHint> = LinkHint::matching(&self.link_hints, typed).collect();
+        match matching[..] {
+            [hint] if hint.label == typed => {
+                let target = hint.link.target;
+                self.cancel_link_hints();
+                self.go_to_page(target);
+            }
+            [] => self.cancel_link_hints(),
+            _ => emit!(RenderPdf),
+        }
+    }
+
+    pub(crate) fn cancel_link_hints(&mut self) {
+        self.link_hints.clear();
+        emit!(ChangeState(AppState::Normal));
+        emit!(RenderPdf);
+    }
+
+    /// Pages shown in the preview.
+    fn visible_pages(&self) -> Vec<u32> {
+        if self.layout != PageLayout::Continuous {
+            return [Some(self.cur_page), self.right_page()].into_iter().flatten().collect();
+        }
+        let pane_height = self.pdf_size.height() as u32;
+        let mut pages = vec![];
+        let (mut page, mut top) = (self.cur_page, 0);
+        let mut offset = self.viewport.1;
+        while top < pane_height && page <= self.pdf_handler.get_page_nums() as u32 {
+            let visible = self.page_height(page).saturating_sub(offset).min(pane_height - top);
+            if visible > 0 {
+                pages.push(page);
+            }
+            top += visible + PAGE_GAP;
+            offset = 0;
+            page += 1;
+        }
+        pages
+    }
+
     pub(crate) fn next_page(&mut self) {
-        if self.cur_page < self.pdf_handler.get_page_nums() as u32 {
-            self.cur_page += 1;
+        let next = match self.right_page() {
+            Some(right) => right + 1,
+            None if self.is_spread() => self.spread_start(self.cur_page) + if self.cur_page == 1 { 1 } else { 2 },
+            None => self.cur_page + 1,
+        };
+        if next <= self.pdf_handler.get_page_nums() as u32 {
+            self.cur_page = next;
             self.already_render = false;
             self.cur_book_mark = self.pdf_handler.find_book_mark_by_page_num(self.cur_page);
 
@@ -163,24 +445,584 @@ impl App {
     }
 
     pub(crate) fn previous_page(&mut self) {
-        if self.cur_page > 0 {
-            self.cur_page -= 1;
+        if self.spread_start(self.cur_page) <= 1 {
+            return;
+        }
+        let previous = if self.is_spread() {
+            self.spread_start(self.spread_start(self.cur_page) - 1)
+        } else {
+            self.cur_page - 1
+        };
+        if previous != self.cur_page {
+            self.cur_page = previous;
             self.already_render = false;
             self.cur_book_mark = self.pdf_handler.find_book_mark_by_page_num(self.cur_page);
         }
     }
 
+    pub(crate) fn toggle_continuous(&mut self) {
+        let layout = if self.layout == PageLayout::Continuous { PageLayout::Single } else { PageLayout::Continuous };
+        self.set_layout(layout);
+    }
+
+    /// 单页 => 双页 => 书本 => 单页
+    pub(crate) fn next_spread_layout(&mut self) {
+        let layout = match self.layout {
+            PageLayout::Spread => PageLayout::Book,
+            PageLayout::Book => PageLayout::Single,
+            _ => PageLayout::Spread,
+        };
+        self.set_layout(layout);
+    }
+
+    fn set_layout(&mut self, layout: PageLayout) {
+        self.layout = layout;
+        self.viewport = (0, 0);
+        self.pdf_size.set_columns(if self.is_spread() { 2 } else { 1 });
+        self.cur_page = self.spread_start(self.cur_page);
+        self.already_render = false;
+        self.update_render_size();
+    }
+
+    pub fn is_spread(&self) -> bool {
+        matches!(self.layout, PageLayout::Spread | PageLayout::Book)
+    }
+
+    /// Left page of the spread `page_id` belongs to.
+    fn spread_start(&self, page_id: u32) -> u32 {
+        match self.layout {
+            PageLayout::Spread if page_id > 1 && page_id.is_multiple_of(2) => page_id - 1,
+            PageLayout::Book if page_id > 1 && !page_id.is_multiple_of(2) => page_id - 1,
+            _ => page_id,
+        }
+    }
+
+    /// Page shown right of `cur_page` in the spread layouts, if any.
+    pub fn right_page(&self) -> Option<u32> {
+        let right = self.cur_page + 1;
+        let paired = match self.layout {
+            PageLayout::Spread => true,
+            PageLayout::Book => self.cur_page > 1,
+            _ => false,
+        };
+        (paired && right <= self.pdf_handler.get_page_nums() as u32).then_some(right)
+    }
+
+    /// Page covering most of the preview, which is `cur_page` unless in continuous mode.
+    pub fn reading_page(&self) -> u32 {
+        if self.layout != PageLayout::Continuous {
+            return self.cur_page;
+        }
+        let pane_height = self.pdf_size.height() as u32;
+        let (mut best, mut best_height) = (self.cur_page, 0);
+        let (mut page, mut top) = (self.cur_page, 0);
+        let mut offset = self.viewport.1;
+        while top < pane_height && page <= self.pdf_handler.get_page_nums() as u32 {
+            let page_height = self.page_height(page);
+            let visible = page_height.saturating_sub(offset).min(pane_height - top);
+            if visible > best_height {
+                (best, best_height) = (page, visible);
+            }
+            top += visible + PAGE_GAP;
+            offset = 0;
+            page += 1;
+        }
+        best
+    }
+
+    fn page_height(&self, page_id: u32) -> u32 {
+        self.page_pixel_size(page_id).1
+    }
+
+    fn page_pixel_size(&self, page_id: u32) -> (u32, u32) {
+        let page_sizes = self.pdf_handler.get_page_sizes();
+        let page_size = page_sizes.get(page_id.saturating_sub(1) as usize).copied().unwrap_or((612.0, 792.0));
+        self.pdf_size.fit(page_size)
+    }
+
+    /// Continuous mode scrolls by lines across page boundaries.
+    fn scroll_continuous(&mut self, down: bool) {
+        let step = self.scroll_step().1;
+        let page_nums = self.pdf_handler.get_page_nums() as u32;
+        if down {
+            self.viewport.1 += step;
+            while self.cur_page < page_nums && self.viewport.1 >= self.page_height(self.cur_page) + PAGE_GAP {
+                self.viewport.1 -= self.page_height(self.cur_page) + PAGE_GAP;
+                self.cur_page += 1;
+            }
+            if self.cur_page == page_nums {
+                let max_y = self.page_height(page_nums).saturating_sub(self.pdf_size.height() as u32);
+                self.viewport.1 = self.viewport.1.min(max_y);
+            }
+        } else if self.viewport.1 >= step {
+            self.viewport.1 -= step;
+        } else if self.cur_page > 1 {
+            self.cur_page -= 1;
+            self.viewport.1 = (self.page_height(self.cur_page) + PAGE_GAP).saturating_sub(step - self.viewport.1);
+        } else {
+            self.viewport.1 = 0;
+        }
+        self.cur_book_mark = self.pdf_handler.find_book_mark_by_page_num(self.reading_page());
+        emit!(RenderPdf);
+    }
+
+    pub(crate) fn toggle_text_mode(&mut self) {
+        self.text_mode = !self.text_mode;
+        self.text_scroll = 0;
+        self.text_selection = None;
+        if !self.text_mode {
+            self.already_render = false;
+        }
+        // 图片和文本互相覆盖不干净, 整屏重画
+        emit!(ClearScreen);
+    }
+
+    /// Start selecting lines from the top of the text pane, or stop.
+    pub(crate) fn toggle_text_selection(&mut self) {
+        self.text_selection = match self.text_selection {
+            None if self.text_mode && !self.text_lines.is_empty() => {
+                let line = (self.text_scroll as usize).min(self.text_lines.len() - 1);
+                Some((line, line))
+            }
+            _ => None,
+        };
+    }
+
+    /// Copy the selected lines, or the text of the current page, to the clipboard.
+    pub(crate) fn copy_text(&mut self) -> anyhow::Result<()> {
+        let text = match self.text_selection.take() {
+            Some((anchor, cursor)) => self.text_lines.get(anchor.min(cursor)..=anchor.max(cursor)).unwrap_or_default().join("\n"),
+            None => self.reading_text(self.reading_page()).to_string(),
+        };
+        if text.is_empty() {
+            self.status = Some(String::from("没有可复制的文本"));
+            return Ok(());
+        }
+        clipboard::copy(&text, self.image_handler.get_multiplexer())?;
+        self.status = Some(format!("已复制 {} 字", text.chars().count()));
+        Ok(())
+    }
+
+    /// Scroll the text by a line, going on with the next/previous page at its end.
+    fn scroll_text(&mut self, down: bool) {
+        if let Some((_, cursor)) = self.text_selection.as_mut() {
+            // 选择时移动光标行, 不翻页
+            *cursor = if down { (*cursor + 1).min(self.text_lines.len().saturating_sub(1)) } else { cursor.saturating_sub(1) };
+            let (cursor, height) = (*cursor as u16, self.text_height.max(1));
+            self.text_scroll = self.text_scroll.clamp(cursor.saturating_sub(height - 1), cursor);
+            return;
+        }
+        let (lines, height) = (self.text_lines.len() as u16, self.text_height);
+        if down {
+            if self.text_scroll + height < lines {
+                self.text_scroll += 1;
+            } else if self.cur_page < self.pdf_handler.get_page_nums() as u32 {
+                self.cur_page += 1;
+                self.text_scroll = 0;
+            }
+        } else if self.text_scroll > 0 {
+            self.text_scroll -= 1;
+        } else if self.cur_page > 1 {
+            self.cur_page -= 1;
+            // 超出的部分在绘制时截到最后一屏
+            self.text_scroll = u16::MAX;
+        }
+        self.cur_book_mark = self.pdf_handler.find_book_mark_by_page_num(self.cur_page);
+    }
+
+    /// Pan down, turning to the next page once the bottom edge is visible.
+    pub(crate) fn scroll_down(&mut self) {
+        if self.text_mode {
+            return self.scroll_text(true);
+        }
+        if self.layout == PageLayout::Continuous {
+            return self.scroll_continuous(true);
+        }
+        let (_, max_y) = self.max_viewport();
+        if self.viewport.1 >= max_y {
+            if self.cur_page < self.pdf_handler.get_page_nums() as u32 {
+                self.viewport.1 = 0;
+                self.next_page();
+            }
+        } else {
+            self.viewport.1 = (self.viewport.1 + self.scroll_step().1).min(max_y);
+            emit!(RenderPdf);
+        }
+    }
+
+    /// Pan up, turning to the bottom of the previous page once the top edge is visible.
+    pub(crate) fn scroll_up(&mut self) {
+        if self.text_mode {
+            return self.scroll_text(false);
+        }
+        if self.layout == PageLayout::Continuous {
+            return self.scroll_continuous(false);
+        }
+        if self.viewport.1 == 0 {
+            if self.cur_page > 1 {
+                self.previous_page();
+                self.viewport.1 = self.max_viewport().1;
+            }
+        } else {
+            self.viewport.1 = self.viewport.1.saturating_sub(self.scroll_step().1);
+            emit!(RenderPdf);
+        }
+    }
+
+    pub(crate) fn scroll_left(&mut self) {
+        if self.viewport.0 > 0 {
+            self.viewport.0 = self.viewport.0.saturating_sub(self.scroll_step().0);
+            emit!(RenderPdf);
+        }
+    }
+
+    pub(crate) fn scroll_right(&mut self) {
+        let (max_x, _) = self.max_viewport();
+        if self.viewport.0 < max_x {
+            self.viewport.0 = (self.viewport.0 + self.scroll_step().0).min(max_x);
+            emit!(RenderPdf);
+        }
+    }
+
+    /// Pixels moved by one pan: a few cells.
+    fn scroll_step(&self) -> (u32, u32) {
+        let (cell_width, cell_height) = self.image_handler.get_cell_size();
+        (cell_width as u32 * 8, cell_height as u32 * 3)
+    }
+
+    /// Largest viewport offset that still fills the preview.
+    fn max_viewport(&self) -> (u32, u32) {
+        let (mut width, mut height) = self.page_pixel_size(self.cur_page);
+        if let Some(right) = self.right_page() {
+            let (right_width, right_height) = self.page_pixel_size(right);
+            width += PAGE_GAP + right_width;
+            height = height.max(right_height);
+        }
+        (width.saturating_sub(self.pdf_size.width() as u32), height.saturating_sub(self.pdf_size.height() as u32))
+    }
+
+    /// Decoded page from the cache, asking for it to be converted if it isn't there yet.
+    async fn load_page(&self, page_id: u32) -> anyhow::Result<Option<PageImage>> {
+        if !self.page_cache.page_exists(page_id).await {
+            emit!(LoadingFirst(page_id));
+            return Ok(None);
+        }
+        match self.page_cache.load_page_data(page_id) {
+            Ok(data) => {
+                let page = self.highlight_search_hits(page_id, PageImage::from_jpeg(data)?);
+                Ok(Some(self.draw_link_hints(page_id, page)))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                emit!(LoadingFirst(page_id));
+                Ok(None)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Load the full-text index, building it when missing or stale, without blocking the ui.
+    /// Waits for the password of a locked pdf and skips one lopdf can't decrypt.
+    pub fn start_indexing(&self) {
+        if self.pdf_handler.is_locked() || self.pdf_handler.is_render_only() {
+            return;
+        }
+        let pdf_path = self.pdf_handler.get_pdf_path().to_string();
+        let index_path = self.page_cache.index_path();
+        let password = self.pdf_handler.get_password().map(str::to_string);
+        tokio::task::spawn_blocking(move || {
+            if let Ok(index) = SearchIndex::open(&pdf_path, &index_path, password.as_deref()) {
+                emit!(IndexReady(Arc::new(index)));
+            }
+        });
+    }
+
+    /// Extracted text of a page, from the index once it is ready.
+    pub fn page_text(&self, page_id: u32) -> Option<&PageText> {
+        match self.search_index.as_ref() {
+            Some(index) => index.page(page_id),
+            None => self.pdf_handler.get_page_text(page_id),
+        }
+    }
+
+    /// Text of a page in reading order, kept for the page last asked for.
+    pub fn reading_text(&mut self, page_id: u32) -> &str {
+        if self.reading_text.as_ref().map(|(page, _)| *page) != Some(page_id) {
+            let text = self.page_text(page_id).map(PageText::reading_order).unwrap_or_default();
+            self.reading_text = Some((page_id, text));
+        }
+        self.reading_text.as_ref().map_or("", |(_, text)| text)
+    }
+
+    /// Tint the search hits on the page, the selected one stronger.
+    fn highlight_search_hits(&self, page_id: u32, page_image: PageImage) -> PageImage {
+        let selected = self.search_state.selected().and_then(|index| self.search_results.get(index));
+        let mut hits = self.search_results.iter().filter(|hit| hit.page == page_id).peekable();
+        if hits.peek().is_none() {
+            return page_image;
+        }
+        let Some(page) = self.page_text(page_id) else {
+            return page_image;
+        };
+        let mut image = page_image.image.to_rgba8();
+        let size = image.dimensions();
+        for hit in hits {
+            let color = if Some(hit) == selected { HIGHLIGHT_SELECTED } else { HIGHLIGHT };
+            for [x0, y0, x1, y1] in page.boxes(hit.range) {
+                let (ax, ay) = self.pdf_handler.page_point_to_image(page_id, (x0, y0), size);
+                let (bx, by) = self.pdf_handler.page_point_to_image(page_id, (x1, y1), size);
+                let (left, right) = (ax.min(bx).max(0.0) as u32, (ax.max(bx).ceil() as u32).min(size.0));
+                let (top, bottom) = (ay.min(by).max(0.0) as u32, (ay.max(by).ceil() as u32).min(size.1));
+                for y in top..bottom {
+                    for x in left..right {
+                        let pixel = image.get_pixel_mut(x, y);
+                        for channel in 0..3 {
+                            pixel[channel] = ((pixel[channel] as u32 * (255 - HIGHLIGHT_ALPHA) + color[channel] as u32 * HIGHLIGHT_ALPHA) / 255) as u8;
+                        }
+                    }
+                }
+            }
+        }
+        DynamicImage::ImageRgba8(image).into()
+    }
+
+    /// Draw the labels still matching the typed prefix at the top left of their links.
+    fn draw_link_hints(&self, page_id: u32, page_image: PageImage) -> PageImage {
+        let AppState::LinkHint(typed) = &self.app_state else {
+            return page_image;
+        };
+        let mut hints = LinkHint::matching(&self.link_hints, typed).filter(|hint| hint.page == page_id).peekable();
+        if hints.peek().is_none() {
+            return page_image;
+        }
+        let mut image = page_image.image.to_rgba8();
+        let size = image.dimensions();
+        // 约一行字高
+        let scale = (size.1 / 300).max(2);
+        for hint in hints {
+            let [x0, y0, x1, y1] = hint.link.rect;
+            let (ax, ay) = self.pdf_handler.page_point_to_image(page_id, (x0, y0), size);
+            let (bx, by) = self.pdf_handler.page_point_to_image(page_id, (x1, y1), size);
+            let (width, height) = ((hint.label.len() as u32 * 4 + 1) * scale, 7 * scale);
+            let left = (ax.min(bx).max(0.0) as u32).min(size.0.saturating_sub(width));
+            let top = (ay.min(by).max(0.0) as u32).min(size.1.saturating_sub(height));
+            for y in top..(top + height).min(size.1) {
+                for x in left..(left + width).min(size.0) {
+                    image.put_pixel(x, y, Rgba([HIGHLIGHT[0], HIGHLIGHT[1], HIGHLIGHT[2], 255]));
+                }
+            }
+            for (index, digit) in hint.label.bytes().enumerate() {
+                let bitmap = DIGITS[(digit - b'0') as usize % 10];
+                let glyph_left = left + (index as u32 * 4 + 1) * scale;
+                for (row, bits) in bitmap.iter().enumerate() {
+                    for column in 0..3 {
+                        if bits & (0b100 >> column) == 0 {
+                            continue;
+                        }
+                        let (x, y) = (glyph_left + column * scale, top + (row as u32 + 1) * scale);
+                        for dy in 0..scale {
+                            for dx in 0..scale {
+                                if x + dx < size.0 && y + dy < size.1 {
+                                    image.put_pixel(x + dx, y + dy, Rgba([0, 0, 0, 255]));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        DynamicImage::ImageRgba8(image).into()
+    }
+
+    /// The image to show in the preview, None while a page it needs is being converted.
+    pub async fn page_image(&mut self) -> anyhow::Result<Option<PageImage>> {
+        if let AppState::Grid(selected) = self.app_state {
+            return self.compose_grid(selected).map(|image| Some(image.into()));
+        }
+        let image = match self.layout {
+            PageLayout::Continuous => self.stitch_pages().await?,
+            PageLayout::Spread | PageLayout::Book => {
+                self.cur_page = self.spread_start(self.cur_page);
+                self.compose_spread().await?
+            }
+            PageLayout::Single => self.load_page(self.cur_page).await?,
+        };
+        Ok(image.map(|image| self.crop_to_viewport(image)))
+    }
+
+    /// Pages from `cur_page` on, stacked until they fill the preview below the viewport.
+    async fn stitch_pages(&mut self) -> anyhow::Result<Option<PageImage>> {
+        let needed = self.viewport.1 + self.pdf_size.height() as u32;
+        let mut pages = vec![];
+        let mut height = 0;
+        let mut page_id = self.cur_page;
+        while height < needed && page_id <= self.pdf_handler.get_page_nums() as u32 {
+            let Some(page) = self.load_page(page_id).await? else {
+                return Ok(None);
+            };
+            height += page.image.height() + PAGE_GAP;
+            pages.push(page.image);
+            page_id += 1;
+        }
+        if pages.len() == 1 {
+            return Ok(pages.pop().map(PageImage::from));
+        }
+        let width = pages.iter().map(DynamicImage::width).max().unwrap_or(1);
+        let mut image = RgbaImage::from_pixel(width, height.saturating_sub(PAGE_GAP).max(1), Rgba([40, 40, 40, 255]));
+        let mut top = 0;
+        for page in pages.iter() {
+            image.copy_from(page, (width - page.width()) / 2, top)?;
+            top += page.height() + PAGE_GAP;
+        }
+        Ok(Some(DynamicImage::ImageRgba8(image).into()))
+    }
+
+    /// (columns, rows) of the thumbnail grid and the box each thumbnail fits in.
+    pub fn grid_layout(&self) -> (u32, u32, (u32, u32)) {
+        let (width, height) = (self.pdf_size.width().max(1) as u32, self.pdf_size.height().max(1) as u32);
+        let columns = (width / THUMBNAIL_WIDTH).clamp(2, 8);
+        let cell_width = width / columns;
+        // portrait pages are about 1.3 times as high as wide
+        let rows = (height * 10 / (cell_width * 13).max(1)).max(1);
+        let cell_height = height / rows;
+        (columns, rows, (cell_width.saturating_sub(PAGE_GAP * 2).max(1), cell_height.saturating_sub(PAGE_GAP * 2).max(1)))
+    }
+
+    /// Thumbnails of the grid page `selected` is on, converted ones drawn as they arrive.
+    fn compose_grid(&mut self, selected: u32) -> anyhow::Result<DynamicImage> {
+        let (columns, rows, thumbnail_box) = self.grid_layout();
+        let per_grid = columns * rows;
+        let first = (selected.max(1) - 1) / per_grid * per_grid + 1;
+        let last = (first + per_grid - 1).min(self.pdf_handler.get_page_nums() as u32);
+        self.page_cache.set_thumbnail_box(thumbnail_box);
+        self.page_cache.load_thumbnails(self.pdf_handler.get_pdf_path(), (first..=last).collect());
+
+        let (cell_width, cell_height) = (thumbnail_box.0 + PAGE_GAP * 2, thumbnail_box.1 + PAGE_GAP * 2);
+        let mut image = RgbaImage::from_pixel(cell_width * columns, cell_height * rows, Rgba([40, 40, 40, 255]));
+        for page_id in first..=last {
+            let index = page_id - first;
+            let (x, y) = (index % columns * cell_width, index / columns * cell_height);
+            if page_id == selected {
+                let border = RgbaImage::from_pixel(cell_width, cell_height, Rgba([220, 50, 50, 255]));
+                image.copy_from(&border, x, y)?;
+            }
+            let thumbnail = self.page_cache.load_thumbnail_data(page_id).ok()
+                .and_then(|data| image::load_from_memory(&data).ok());
+            match thumbnail {
+                Some(thumbnail) => {
+                    let thumbnail = thumbnail.thumbnail(thumbnail_box.0, thumbnail_box.1);
+                    let (dx, dy) = ((cell_width - thumbnail.width()) / 2, (cell_height - thumbnail.height()) / 2);
+                    image.copy_from(&thumbnail, x + dx, y + dy)?;
+                }
+                None => {
+                    // still converting, or failed
+                    let color = if self.page_cache.thumbnail_failed(page_id) { Rgba([120, 40, 40, 255]) } else { Rgba([90, 90, 90, 255]) };
+                    let placeholder = RgbaImage::from_pixel(thumbnail_box.0, thumbnail_box.1, color);
+                    image.copy_from(&placeholder, x + PAGE_GAP, y + PAGE_GAP)?;
+                }
+            }
+        }
+        Ok(DynamicImage::ImageRgba8(image))
+    }
+
+    /// Leave the grid at the selected page.
+    pub(crate) fn jump_to_grid_page(&mut self, page_id: u32) {
+        self.go_to_page(page_id);
+    }
+
+    /// Show `page_id` from its top, remembering the page left in the jump list.
+    pub(crate) fn go_to_page(&mut self, page_id: u32) {
+        self.jump_list.push(self.reading_page(), page_id);
+        self.show_page(page_id);
+    }
+
+    pub(crate) fn jump_back(&mut self) {
+        if let Some(page_id) = self.jump_list.back(self.reading_page()) {
+            self.show_page(page_id);
+        }
+    }
+
+    pub(crate) fn jump_forward(&mut self) {
+        if let Some(page_id) = self.jump_list.forward(self.reading_page()) {
+            self.show_page(page_id);
+        }
+    }
+
+    fn show_page(&mut self, page_id: u32) {
+        self.cur_page = page_id;
+        self.viewport.1 = 0;
+        self.already_render = false;
+        self.cur_book_mark = self.pdf_handler.find_book_mark_by_page_num(self.cur_page);
+    }
+
+    /// `cur_page` and the page facing it side by side.
+    async fn compose_spread(&mut self) -> anyhow::Result<Option<PageImage>> {
+        let Some(left) = self.load_page(self.cur_page).await? else {
+            return Ok(None);
+        };
+        let Some(right_page) = self.right_page() else {
+            return Ok(Some(left));
+        };
+        let Some(right) = self.load_page(right_page).await? else {
+            return Ok(None);
+        };
+        let (left, right) = (left.image, right.image);
+        let (width, height) = (left.width() + PAGE_GAP + right.width(), left.height().max(right.height()));
+        let mut image = RgbaImage::from_pixel(width, height, Rgba([40, 40, 40, 255]));
+        image.copy_from(&left, 0, (height - left.height()) / 2)?;
+        image.copy_from(&right, left.width() + PAGE_GAP, (height - right.height()) / 2)?;
+        Ok(Some(DynamicImage::ImageRgba8(image).into()))
+    }
+
+    /// Crop the page to the part visible in the preview.
+    pub fn crop_to_viewport(&mut self, page_image: PageImage) -> PageImage {
+        let (pane_width, pane_height) = (self.pdf_size.width() as u32, self.pdf_size.height() as u32);
+        let image = &page_image.image;
+        if image.width() <= pane_width && image.height() <= pane_height {
+            return page_image;
+        }
+        let x = self.viewport.0.min(image.width().saturating_sub(pane_width));
+        let y = self.viewport.1.min(image.height().saturating_sub(pane_height));
+        self.viewport = (x, y);
+        image.crop_imm(x, y, pane_width, pane_height).into()
+    }
+
     pub(crate) fn increment_pdf_size(&mut self) {
         self.already_render = false;
-        self.pdf_size.increment();
+        self.pdf_size.increment(self.cur_page_size());
+        self.update_render_size();
     }
 
     pub(crate) fn decrement_pdf_size(&mut self) {
         self.already_render = false;
-        self.pdf_size.decrement();
+        self.pdf_size.decrement(self.cur_page_size());
+        self.update_render_size();
         // clear screen
     }
 
+    /// 整页 / 页宽 / 页高 切换
+    pub(crate) fn next_zoom_mode(&mut self) {
+        self.set_zoom(self.pdf_size.zoom().next());
+    }
+
+    pub fn set_zoom(&mut self, zoom: ZoomMode) {
+        self.already_render = false;
+        self.pdf_size.set_zoom(zoom);
+        self.update_render_size();
+    }
+
+    /// 当前页大小, 单位 pt
+    pub fn cur_page_size(&self) -> (f32, f32) {
+        let page_sizes = self.pdf_handler.get_page_sizes();
+        page_sizes.get(self.cur_page.saturating_sub(1) as usize).copied().unwrap_or((612.0, 792.0))
+    }
+
+    /// Render pages at the preview size, restarting the background loading if it changed.
+    pub(crate) fn update_render_size(&mut self) {
+        if self.page_cache.set_pdf_size(&self.pdf_size) {
+            self.next_load_page = 1;
+        }
+    }
+
     #[allow(dead_code)]
     fn get_current_book_mark_index(&self) -> Option<&BookMarkIndex> {
         if let Some(index) = self.book_marks_state.selected() {
@@ -191,3 +1033,79 @@ impl App {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use lopdf::{dictionary, Document, Object};
+    use crate::pdf::PageLink;
+    use crate::rasterizer::Pdftoppm;
+    use super::{App, LinkHint, PageLayout};
+
+    /// App on a blank pdf of `page_count` pages written to a temporary directory
+    fn app(name: &str, page_count: usize) -> App {
+        let mut document = Document::with_version("1.5");
+        let pages_id = document.new_object_id();
+        let kids: Vec<Object> = (0..page_count)
+            .map(|_| document.add_object(dictionary! { "Type" => "Page", "Parent" => pages_id }).into())
+            .collect();
+        document.objects.insert(pages_id, dictionary! { "Type" => "Pages", "Kids" => kids, "Count" => page_count as i64 }.into());
+        let catalog_id = document.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        document.trailer.set("Root", catalog_id);
+        let dir = std::env::temp_dir().join(format!("rpr-app-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("blank.pdf");
+        document.save(&path).unwrap();
+        App::new(path.to_str().unwrap(), 1, Arc::new(Pdftoppm), None).unwrap()
+    }
+
+    #[test]
+    fn previous_page_stops_at_first_page() {
+        let mut app = app("previous", 6);
+        for layout in [PageLayout::Single, PageLayout::Spread, PageLayout::Book] {
+            app.layout = layout;
+            app.cur_page = 1;
+            app.previous_page();
+            assert_eq!(app.cur_page, 1, "{layout:?}");
+        }
+        app.layout = PageLayout::Spread;
+        app.cur_page = 4;
+        app.previous_page();
+        assert_eq!(app.cur_page, 1);
+        app.layout = PageLayout::Book;
+        app.cur_page = 3;
+        app.previous_page();
+        assert_eq!(app.cur_page, 1);
+        app.cur_page = 5;
+        app.previous_page();
+        assert_eq!(app.cur_page, 2);
+        std::fs::remove_dir_all(std::env::temp_dir().join(format!("rpr-app-previous-{}", std::process::id()))).unwrap();
+    }
+
+    fn labels<'a>(hints: impl Iterator<Item = &'a LinkHint>) -> Vec<&'a str> {
+        hints.map(|hint| hint.label.as_str()).collect()
+    }
+
+    #[test]
+    fn link_hint_labels() {
+        let links = (1..=12).map(|target| (target / 5 + 1, PageLink { rect: [0.0; 4], target })).collect();
+        let hints = LinkHint::label(links);
+        assert_eq!(hints[0].label, "01");
+        assert_eq!(hints[11].label, "12");
+        assert_eq!((hints[11].page, hints[11].link.target), (3, 12));
+        let single = LinkHint::label(vec![(1, PageLink { rect: [0.0; 4], target: 2 })]);
+        assert_eq!(single[0].label, "1");
+    }
+
+    #[test]
+    fn link_hint_filtering() {
+        let links = (1..=12).map(|target| (1, PageLink { rect: [0.0; 4], target })).collect();
+        let hints = LinkHint::label(links);
+        assert_eq!(LinkHint::matching(&hints, "").count(), 12);
+        assert_eq!(labels(LinkHint::matching(&hints, "1")), vec!["10", "11", "12"]);
+        assert_eq!(LinkHint::matching(&hints, "0").count(), 9);
+        assert_eq!(labels(LinkHint::matching(&hints, "07")), vec!["07"]);
+        assert_eq!(LinkHint::matching(&hints, "2").count(), 0);
+        assert_eq!(LinkHint::matching(&hints, "123").count(), 0);
+    }
+}
@@ -1,54 +1,130 @@
-use std::collections::HashMap;
-use std::ffi::OsString;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fmt::Display;
 use std::{fs, io};
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::sync::Arc;
+use std::time::SystemTime;
 use image::codecs::jpeg::JpegEncoder;
 use image::io::Reader as ImageReader;
 use crate::emit;
+use crate::error::{Error, Result};
+use crate::index::INDEX_FILE;
+use crate::pdf::PdfSize;
+use crate::rasterizer::Rasterizer;
 
+/// Render sizes kept on disk for a pdf, and as many thumbnail sizes,
+/// the least recently used ones are deleted past it
+const MAX_SIZE_DIRS: usize = 3;
+const THUMBNAIL_PREFIX: &str = "thumb-";
 
 #[derive(Debug)]
 pub struct FileCache {
+    /// `<pdf>-rpr` directory
+    root: PathBuf,
+    /// directory of the current render size, inside `root`
     path: PathBuf,
     page_queue: Vec<u32>,
     cache: HashMap<u32, bool>,
+    /// pages the rasterizer failed on, not tried again
+    failed: HashSet<u32>,
+    rasterizer: Arc<dyn Rasterizer>,
+    /// password of an encrypted pdf, handed to the rasterizer
+    password: Option<String>,
+    /// page sizes in pt, indexed by page id - 1
+    page_sizes: Vec<(f32, f32)>,
+    /// preview size the pages are rendered for
+    pdf_size: PdfSize,
+    /// directory of the grid view thumbnails, inside `root`
+    thumbnail_path: PathBuf,
+    thumbnail_cache: HashMap<u32, bool>,
+    /// thumbnails the rasterizer failed on, tried again at another size
+    thumbnail_failed: HashSet<u32>,
+    /// size every thumbnail is fit into
+    thumbnail_box: (u32, u32),
 }
 
 impl FileCache {
-    pub fn new(path: String) -> Self {
+    pub fn new(path: String, rasterizer: Arc<dyn Rasterizer>, page_sizes: Vec<(f32, f32)>, pdf_size: PdfSize) -> Result<Self> {
         let path = PathBuf::from(&path);
-        let parent = path.parent().unwrap();
-        let file_name = path.file_stem().unwrap();
-        let path = parent.join(format!("{}-rpr", OsString::from(file_name).into_string().unwrap()));
-        let cache = Self::init_page_cache(&path);
-        Self {
+        let parent = path.parent().unwrap_or(Path::new("."));
+        let file_name = path.file_stem().unwrap_or(path.as_os_str()).to_string_lossy();
+        let root = parent.join(format!("{file_name}-rpr"));
+        if !root.exists() {
+            fs::create_dir(&root).map_err(Error::io(&root))?;
+        }
+        let path = Self::size_dir(&root, &pdf_size);
+        let cache = Self::init_page_cache(&path)?;
+        prune_size_dirs(&root, &path);
+        Ok(Self {
+            root,
             path,
             page_queue: Vec::new(),
             cache,
+            failed: HashSet::new(),
+            rasterizer,
+            password: None,
+            page_sizes,
+            pdf_size,
+            thumbnail_path: PathBuf::new(),
+            thumbnail_cache: HashMap::new(),
+            thumbnail_failed: HashSet::new(),
+            thumbnail_box: (0, 0),
+        })
+    }
+
+    /// Pages rendered for different preview sizes live in their own directory.
+    fn size_dir(root: &Path, pdf_size: &PdfSize) -> PathBuf {
+        root.join(pdf_size.cache_key())
+    }
+
+    /// Switch to the preview size, returns whether it changed.
+    pub fn set_pdf_size(&mut self, pdf_size: &PdfSize) -> bool {
+        let path = Self::size_dir(&self.root, pdf_size);
+        if path == self.path {
+            return false;
         }
+        // 目录无法创建时页面转换失败, 在状态栏提示
+        self.cache = Self::init_page_cache(&path).unwrap_or_default();
+        prune_size_dirs(&self.root, &path);
+        self.path = path;
+        self.page_queue.clear();
+        // 换个大小可能就能渲染了
+        self.failed.clear();
+        self.pdf_size = pdf_size.clone();
+        true
+    }
+
+    /// Where the full-text search index of the pdf is kept.
+    pub fn index_path(&self) -> PathBuf {
+        self.root.join(INDEX_FILE)
     }
 
-    pub fn init_page_cache(path: &PathBuf) -> HashMap<u32, bool> {
+    /// Pixel size page `page_id` is rendered at.
+    pub fn page_pixel_size(&self, page_id: u32) -> (u32, u32) {
+        let page_size = self.page_sizes.get(page_id.saturating_sub(1) as usize).copied().unwrap_or((612.0, 792.0));
+        self.pdf_size.fit(page_size)
+    }
+
+    pub fn init_page_cache(path: &PathBuf) -> Result<HashMap<u32, bool>> {
         let mut cache = HashMap::new();
         if !path.exists() {
-            fs::create_dir(&path).expect("error to create pdf directory");
+            fs::create_dir(path).map_err(Error::io(path))?;
         }
-        for entry in path.read_dir().expect("read_dir call failed") {
-            if let Ok(entry) = entry {
-                let entry_path = entry.path();
-                let page_id = OsString::from(entry_path.file_stem().unwrap()).into_string().unwrap();
-                if let Ok(page_id) = page_id.parse::<u32>() {
-                    cache.insert(page_id, true);
-                }
+        for entry in path.read_dir().map_err(Error::io(path))?.flatten() {
+            let entry_path = entry.path();
+            let page_id = entry_path.file_stem().and_then(OsStr::to_str).and_then(|stem| stem.parse::<u32>().ok());
+            if let Some(page_id) = page_id {
+                cache.insert(page_id, true);
             }
         }
-        cache
+        Ok(cache)
     }
 
     pub async fn page_exists(&self, page_id: u32) -> bool {
-        return *self.cache.get(&page_id).unwrap_or(&false);
+        *self.cache.get(&page_id).unwrap_or(&false)
     }
 
     pub fn get_page_path(&self, page_id: u32) -> String {
@@ -56,7 +132,7 @@ impl FileCache {
     }
 
     pub fn load_page_data(&self, page_id: u32) -> io::Result<Vec<u8>> {
-        fs::read(&self.get_page_path(page_id))
+        fs::read(self.get_page_path(page_id))
     }
 
     pub async fn load_next_page(&mut self, pdf_path: &str, next_page_id: Option<u32>) {
@@ -64,14 +140,30 @@ impl FileCache {
             let id = *id;
             self.page_queue.remove(0);
             let page_path = self.get_page_path(id);
-            self.convert_pdf_to_ppm(pdf_path.to_string(), page_path, id).await.unwrap();
+            self.convert_pdf_to_ppm(pdf_path.to_string(), page_path, id).await;
         }
         if let Some(next_page_id) = next_page_id {
             self.page_queue.push(next_page_id);
         }
     }
 
+    /// Pages failed without the password are tried again with it.
+    pub fn set_password(&mut self, password: Option<String>) {
+        self.password = password;
+        self.failed.clear();
+        self.thumbnail_failed.clear();
+    }
+
+    /// Stop converting a page the rasterizer failed on, it would only fail again.
+    pub fn set_failed(&mut self, page_id: u32) {
+        self.cache.remove(&page_id);
+        self.failed.insert(page_id);
+    }
+
     pub async fn add_first(&mut self, page_id: u32) {
+        if self.failed.contains(&page_id) {
+            return;
+        }
         if self.page_exists(page_id).await {
             emit!(RenderPdf);
         } else {
@@ -80,33 +172,184 @@ impl FileCache {
         }
     }
 
-    pub async fn convert_pdf_to_ppm(&mut self, pdf_path: String, page_path: String, page_id: u32) -> anyhow::Result<()> {
+    pub async fn convert_pdf_to_ppm(&mut self, pdf_path: String, page_path: String, page_id: u32) {
+        if self.failed.contains(&page_id) {
+            return;
+        }
         if let Some(page) = self.cache.get(&page_id) {
             if *page {
                 emit!(RenderPdf);
                 // already convert
-                return Ok(());
+                return;
             }
         }
         self.cache.insert(page_id, true);
+        let rasterizer = self.rasterizer.clone();
+        let password = self.password.clone();
+        let size = self.page_pixel_size(page_id);
+        tokio::spawn(async move {
+            match convert_page(rasterizer.as_ref(), &pdf_path, password.as_deref(), page_id, size, &page_path) {
+                Ok(()) => emit!(RenderPdf),
+                // the size was pruned while the page was converted for it
+                Err(Error::Io { .. }) if !Path::new(&page_path).parent().is_some_and(Path::exists) => {}
+                Err(e) => emit!(RenderFailed(page_id, e.to_string())),
+            }
+        });
+    }
+
+    /// Thumbnails are rendered to fit `thumbnail_box` and kept apart from the pages.
+    pub fn set_thumbnail_box(&mut self, thumbnail_box: (u32, u32)) {
+        let path = self.root.join(format!("{THUMBNAIL_PREFIX}{}x{}", thumbnail_box.0, thumbnail_box.1));
+        if path != self.thumbnail_path {
+            self.thumbnail_cache = Self::init_page_cache(&path).unwrap_or_default();
+            prune_size_dirs(&self.root, &path);
+            self.thumbnail_failed.clear();
+            self.thumbnail_path = path;
+            self.thumbnail_box = thumbnail_box;
+        }
+    }
+
+    pub fn thumbnail_exists(&self, page_id: u32) -> bool {
+        *self.thumbnail_cache.get(&page_id).unwrap_or(&false)
+    }
+
+    /// Stop converting a thumbnail the rasterizer failed on until the size changes.
+    pub fn set_thumbnail_failed(&mut self, page_id: u32) {
+        self.thumbnail_cache.remove(&page_id);
+        self.thumbnail_failed.insert(page_id);
+    }
+
+    pub fn thumbnail_failed(&self, page_id: u32) -> bool {
+        self.thumbnail_failed.contains(&page_id)
+    }
+
+    pub fn get_thumbnail_path(&self, page_id: u32) -> String {
+        format!("{}/{page_id}.jpeg", self.thumbnail_path.display())
+    }
+
+    pub fn load_thumbnail_data(&self, page_id: u32) -> io::Result<Vec<u8>> {
+        fs::read(self.get_thumbnail_path(page_id))
+    }
+
+    /// Convert the missing thumbnails one after another, emitting RenderPdf after each.
+    pub fn load_thumbnails(&mut self, pdf_path: &str, page_ids: Vec<u32>) {
+        let mut jobs = vec![];
+        for page_id in page_ids {
+            if self.thumbnail_exists(page_id) || self.thumbnail_failed(page_id) {
+                continue;
+            }
+            self.thumbnail_cache.insert(page_id, true);
+            let page_size = self.page_sizes.get(page_id.saturating_sub(1) as usize).copied().unwrap_or((612.0, 792.0));
+            let (width, height) = self.thumbnail_box;
+            let scale = (width as f32 / page_size.0).min(height as f32 / page_size.1);
+            let size = (((page_size.0 * scale) as u32).max(1), ((page_size.1 * scale) as u32).max(1));
+            jobs.push((page_id, size, self.get_thumbnail_path(page_id)));
+        }
+        if jobs.is_empty() {
+            return;
+        }
+        let rasterizer = self.rasterizer.clone();
+        let password = self.password.clone();
+        let pdf_path = pdf_path.to_string();
         tokio::spawn(async move {
-            let data = Command::new("pdftoppm")
-                .args(["-jpeg", "-jpegopt", "quality=70", "-f", &format!("{page_id}"), &pdf_path.clone()])
-                .output()
-                .expect("convert pdf failed");
-            let image_data = data.stdout;
-            let img = ImageReader::new(Cursor::new(image_data)).with_guessed_format().unwrap().decode().unwrap();
-            let mut jpg = vec![];
-            match JpegEncoder::new_with_quality(&mut jpg, 70).encode_image(&img) {
-                Ok(_) => {}
-                Err(e) => {
-                    // TODO error user tip
-                    println!("convert image error => {e}");
+            for (page_id, size, thumbnail_path) in jobs {
+                match convert_page(rasterizer.as_ref(), &pdf_path, password.as_deref(), page_id, size, &thumbnail_path) {
+                    Ok(()) => emit!(RenderPdf),
+                    Err(e) => emit!(ThumbnailFailed(page_id, e.to_string())),
                 }
-            };
-            fs::write(Path::new(&page_path), &jpg).expect("write page data error");
-            emit!(RenderPdf);
+            }
         });
-        Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Mark `dir` as just used and delete the least recently used directories
+/// of the same kind, pages or thumbnails, past [`MAX_SIZE_DIRS`].
+fn prune_size_dirs(root: &Path, dir: &Path) {
+    let is_thumbnail = |path: &Path| path.file_name().and_then(OsStr::to_str).is_some_and(|name| name.starts_with(THUMBNAIL_PREFIX));
+    if let Ok(file) = fs::File::open(dir) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+    let Ok(entries) = root.read_dir() else {
+        return;
+    };
+    let mut dirs: Vec<(SystemTime, PathBuf)> = entries.flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| entry.path())
+        .filter(|path| path != dir && is_thumbnail(path) == is_thumbnail(dir))
+        .filter_map(|path| Some((path.metadata().ok()?.modified().ok()?, path)))
+        .collect();
+    // newest first, `dir` itself is the first one kept
+    dirs.sort_by_key(|(modified, _)| Reverse(*modified));
+    for (_, path) in dirs.into_iter().skip(MAX_SIZE_DIRS - 1) {
+        let _ = fs::remove_dir_all(path);
+    }
+}
+
+/// Render the page with the rasterizer and store it as jpeg at `page_path`.
+fn convert_page(rasterizer: &dyn Rasterizer, pdf_path: &str, password: Option<&str>, page_id: u32, size: (u32, u32), page_path: &str) -> Result<()> {
+    let render_error = |e: &dyn Display| Error::render(page_id, e);
+    let image_data = rasterizer.render_page(pdf_path, page_id, size, password).map_err(|e| render_error(&e))?;
+    let img = ImageReader::new(Cursor::new(image_data)).with_guessed_format()
+        .map_err(|e| render_error(&e))?
+        .decode()
+        .map_err(|e| render_error(&e))?;
+    let mut jpg = vec![];
+    JpegEncoder::new_with_quality(&mut jpg, 70).encode_image(&img).map_err(|e| render_error(&e))?;
+    // write then rename, so a page being written is never read half done
+    let tmp_path = format!("{page_path}.tmp");
+    fs::write(Path::new(&tmp_path), &jpg).map_err(Error::io(&tmp_path))?;
+    fs::rename(&tmp_path, page_path).map_err(Error::io(page_path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+    use crate::pdf::PdfSize;
+    use crate::rasterizer::Pdftoppm;
+    use super::{prune_size_dirs, FileCache, MAX_SIZE_DIRS};
+
+    #[test]
+    fn prune_least_recently_used() {
+        let root = std::env::temp_dir().join(format!("rpr-prune-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let names = ["page-100x200", "width-80", "zoom-150", "height-300", "thumb-10x10", "thumb-20x20"];
+        for (age, name) in names.iter().enumerate() {
+            let dir = root.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("1.jpeg"), b"").unwrap();
+            let modified = SystemTime::now() - Duration::from_secs(100 - age as u64);
+            fs::File::open(&dir).unwrap().set_modified(modified).unwrap();
+        }
+        fs::write(root.join("index.json"), b"{}").unwrap();
+
+        // the oldest one is switched to again, the next oldest goes
+        prune_size_dirs(&root, &root.join("page-100x200"));
+        let mut left: Vec<String> = fs::read_dir(&root).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["height-300", "index.json", "page-100x200", "thumb-10x10", "thumb-20x20", "zoom-150"]);
+        assert_eq!(MAX_SIZE_DIRS, 3);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn failed_thumbnail_is_retried_at_another_size() {
+        let dir = std::env::temp_dir().join(format!("rpr-thumb-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let pdf_path = dir.join("a.pdf").to_string_lossy().into_owned();
+        let mut cache = FileCache::new(pdf_path.clone(), Arc::new(Pdftoppm), vec![], PdfSize::new(100, 100, 0, 0)).unwrap();
+        cache.set_thumbnail_box((10, 10));
+        cache.set_thumbnail_failed(1);
+        assert!(!cache.thumbnail_exists(1));
+        assert!(cache.thumbnail_failed(1));
+        // nothing is spawned for the failed page, there is no runtime to spawn on
+        cache.load_thumbnails(&pdf_path, vec![1]);
+        cache.set_thumbnail_box((20, 20));
+        assert!(!cache.thumbnail_failed(1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,35 @@
+use std::io::{stdout, Write};
+use base64::Engine;
+use base64::engine::general_purpose;
+use crate::image::Multiplexer;
+
+/// `OSC 52 ; c ; <base64> BEL` sets the clipboard of the terminal, over ssh too
+pub fn osc52(text: &str) -> Vec<u8> {
+    format!("\x1b]52;c;{}\x07", general_purpose::STANDARD.encode(text)).into_bytes()
+}
+
+/// Copy `text` to the system clipboard through the terminal.
+///
+/// Inside tmux the sequence is passed through to the outer terminal,
+/// which needs `set -g allow-passthrough on` like the images do.
+pub fn copy(text: &str, multiplexer: Option<Multiplexer>) -> anyhow::Result<()> {
+    let buf = osc52(text);
+    let buf = match multiplexer {
+        Some(multiplexer) => multiplexer.passthrough(&buf),
+        None => buf,
+    };
+    let mut stdout = stdout().lock();
+    stdout.write_all(&buf)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::osc52;
+
+    #[test]
+    fn osc52_sequence() {
+        assert_eq!(osc52("中文 text"), b"\x1b]52;c;5Lit5paHIHRleHQ=\x07");
+    }
+}
@@ -1,17 +1,337 @@
-use anyhow::bail;
+use std::collections::HashMap;
 use chardetng::EncodingDetector;
-use encoding_rs::Encoding;
-
-pub fn decode_str_to_utf8(bytes: &[u8]) -> anyhow::Result<String> {
-    let mut detector = EncodingDetector::new();
-    detector.feed(bytes, true);
-    let fed = detector.guess(None, true).name();
-    let encoding = Encoding::for_label(fed.as_bytes());
-    if let Some(encode) = encoding {
-        let (cow, _, has_error) = encode.decode(bytes);
-        if !has_error {
-            return Ok(cow.into());
+use encoding_rs::{BIG5, EUC_KR, GBK, SHIFT_JIS, UTF_16BE};
+use lopdf::{Dictionary, Document, Object};
+
+/// Adobe glyph names
+mod glyph_list;
+
+/// PDFDocEncoding 0x18..=0x1F and 0x80..=0xA0, the rest is Latin-1 (0x7F, 0x9F and 0xAD undefined)
+const PDF_DOC_LOW: [char; 8] = ['˘', 'ˇ', 'ˆ', '˙', '˝', '˛', '˚', '˜'];
+const PDF_DOC_HIGH: [char; 33] = [
+    '•', '†', '‡', '…', '—', '–', 'ƒ', '⁄', '‹', '›', '−', '‰', '„', '“', '”', '‘',
+    '’', '‚', '™', 'ﬁ', 'ﬂ', 'Ł', 'Œ', 'Š', 'Ÿ', 'Ž', 'ı', 'ł', 'œ', 'š', 'ž', '\u{FFFD}', '€',
+];
+
+/// Decode a PDF text string (outline titles, document info): UTF-16BE or UTF-8 with BOM, otherwise
+/// PDFDocEncoding. Many Chinese PDFs store GBK/Big5 bytes instead, which the charset guess catches.
+pub fn decode_text_string(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return UTF_16BE.decode_without_bom_handling(utf16).0.into_owned();
+    }
+    if let Some(utf8) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return String::from_utf8_lossy(utf8).into_owned();
+    }
+    if !bytes.is_ascii() {
+        let mut detector = EncodingDetector::new();
+        detector.feed(bytes, true);
+        let guess = detector.guess(None, true);
+        if [GBK, BIG5, SHIFT_JIS, EUC_KR].contains(&guess) {
+            if let (text, _, false) = guess.decode(bytes) {
+                return text.into_owned();
+            }
+        }
+    }
+    decode_pdf_doc_encoding(bytes)
+}
+
+pub fn decode_pdf_doc_encoding(bytes: &[u8]) -> String {
+    bytes.iter().filter_map(|&b| match b {
+        0x18..=0x1F => Some(PDF_DOC_LOW[(b - 0x18) as usize]),
+        0x80..=0xA0 => Some(PDF_DOC_HIGH[(b - 0x80) as usize]),
+        0x7F | 0xAD => None,
+        _ => Some(b as char),
+    }).filter(|&c| c != '\u{FFFD}').collect()
+}
+
+/// Unicode of an Adobe glyph name, `uniXXXX` and `uXXXX` names included.
+pub fn glyph_to_char(name: &str) -> Option<char> {
+    let lookup = |name: &str| glyph_list::GLYPHS.binary_search_by(|&(glyph, _)| glyph.cmp(name))
+        .ok()
+        .map(|index| glyph_list::GLYPHS[index].1);
+    if let Some(c) = lookup(name) {
+        return Some(c);
+    }
+    // a.sc, uni4E2D.vert 之类的变体取点前部分
+    let base = name.split('.').next().unwrap_or(name);
+    if let Some(c) = lookup(base) {
+        return Some(c);
+    }
+    let hex = base.strip_prefix("uni").filter(|hex| hex.len() == 4)
+        .or_else(|| base.strip_prefix('u').filter(|hex| (4..=6).contains(&hex.len())))?;
+    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+}
+
+/// Code => text table of a simple (single byte) font: base encoding with /Differences applied.
+#[derive(Debug, Clone)]
+pub struct SimpleEncoding {
+    table: Vec<Option<char>>,
+}
+
+impl SimpleEncoding {
+    /// `base` is a standard encoding name, differences are (code, glyph name) pairs.
+    pub fn new(base: &str, differences: &[(u8, &str)]) -> Self {
+        let mut table: Vec<Option<char>> = (0..=255u8)
+            .map(|code| Document::decode_text(Some(base), &[code]).chars().next())
+            .collect();
+        for &(code, name) in differences {
+            table[code as usize] = glyph_to_char(name);
         }
+        Self { table }
+    }
+
+    /// `/Encoding` of a font: a name, or a dictionary with /BaseEncoding and /Differences.
+    pub fn from_font(document: &Document, font: &Dictionary) -> Self {
+        let encoding = font.get(b"Encoding").ok().and_then(|object| document.dereference(object).ok()).map(|(_, object)| object);
+        match encoding {
+            Some(Object::Name(name)) => Self::new(&String::from_utf8_lossy(name), &[]),
+            Some(Object::Dictionary(dict)) => {
+                let base = dict.get(b"BaseEncoding").and_then(Object::as_name_str).unwrap_or("StandardEncoding");
+                let differences = dict.get(b"Differences").ok()
+                    .and_then(|object| document.dereference(object).ok())
+                    .and_then(|(_, object)| object.as_array().ok());
+                // [code name name ... code name ...], 名字依次对应递增的码
+                let mut pairs = vec![];
+                let mut code = 0i64;
+                for item in differences.into_iter().flatten() {
+                    match item {
+                        Object::Integer(start) => code = *start,
+                        Object::Name(name) if (0..=255).contains(&code) => {
+                            pairs.push((code as u8, std::str::from_utf8(name).unwrap_or_default()));
+                            code += 1;
+                        }
+                        _ => {}
+                    }
+                }
+                Self::new(base, &pairs)
+            }
+            _ => Self::new("StandardEncoding", &[]),
+        }
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        bytes.iter().filter_map(|&code| self.table[code as usize]).collect()
+    }
+}
+
+/// Predefined CMaps of Type0 fonts whose codes are UTF-16BE themselves.
+pub fn is_unicode_cmap(name: &str) -> bool {
+    name.starts_with("Uni") && (name.contains("UCS2") || name.contains("UTF16"))
+}
+
+/// ToUnicode CMap of a font: character code => unicode text
+#[derive(Debug, Clone, Default)]
+pub struct ToUnicode {
+    /// (byte length, low, high) of the valid codes
+    code_space: Vec<(usize, u32, u32)>,
+    map: HashMap<(usize, u32), String>,
+}
+
+/// Codes a single bfrange may expand to, guards against broken ranges
+const MAX_RANGE_LEN: u32 = 0x10000;
+
+impl ToUnicode {
+    pub fn parse(data: &[u8]) -> Self {
+        let mut cmap = Self::default();
+        let tokens = cmap_tokens(data);
+        for (index, token) in tokens.iter().enumerate() {
+            let CMapToken::Keyword(keyword) = token else {
+                continue;
+            };
+            // 到下一个关键字 (endxxx) 为止的操作数
+            let operands = &tokens[index + 1..];
+            let operands = &operands[..operands.iter().position(|t| matches!(t, CMapToken::Keyword(_))).unwrap_or(operands.len())];
+            match keyword.as_str() {
+                "begincodespacerange" => {
+                    for pair in operands.chunks_exact(2) {
+                        if let [CMapToken::Hex(low), CMapToken::Hex(high)] = pair {
+                            cmap.code_space.push((low.len(), be_number(low), be_number(high)));
+                        }
+                    }
+                }
+                "beginbfchar" => {
+                    for pair in operands.chunks_exact(2) {
+                        if let [CMapToken::Hex(code), CMapToken::Hex(text)] = pair {
+                            cmap.map.insert((code.len(), be_number(code)), utf16_be(text));
+                        }
+                    }
+                }
+                "beginbfrange" => {
+                    for triple in operands.chunks_exact(3) {
+                        let [CMapToken::Hex(low), CMapToken::Hex(high), target] = triple else {
+                            continue;
+                        };
+                        let (len, low, high) = (low.len(), be_number(low), be_number(high));
+                        let count = high.saturating_sub(low).min(MAX_RANGE_LEN);
+                        for offset in 0..=count {
+                            let text = match target {
+                                CMapToken::Hex(start) => utf16_be(&increment(start, offset)),
+                                CMapToken::Array(texts) => match texts.get(offset as usize) {
+                                    Some(text) => utf16_be(text),
+                                    None => break,
+                                },
+                                CMapToken::Keyword(_) => break,
+                            };
+                            cmap.map.insert((len, low + offset), text);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        cmap
+    }
+
+    /// Decode a shown string, unmapped codes are dropped.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        let mut text = String::new();
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let len = self.code_len(&bytes[pos..]);
+            if let Some(s) = self.map.get(&(len, be_number(&bytes[pos..pos + len]))) {
+                text.push_str(s);
+            }
+            pos += len;
+        }
+        text
+    }
+
+    /// Length of the code at the start of `bytes`, by the code space ranges.
+    pub fn code_len(&self, bytes: &[u8]) -> usize {
+        self.code_space.iter()
+            .find(|&&(len, low, high)| len <= bytes.len() && (low..=high).contains(&be_number(&bytes[..len])))
+            .map(|&(len, _, _)| len)
+            // 没有码空间时按映射中的码长
+            .or_else(|| self.map.keys().next().map(|&(len, _)| len.min(bytes.len())))
+            .unwrap_or(1)
+            .max(1)
+    }
+}
+
+#[derive(Debug)]
+enum CMapToken {
+    Hex(Vec<u8>),
+    Array(Vec<Vec<u8>>),
+    Keyword(String),
+}
+
+/// Hex strings, arrays of hex strings and keywords, everything else is skipped.
+fn cmap_tokens(data: &[u8]) -> Vec<CMapToken> {
+    let mut tokens = vec![];
+    let mut array: Option<Vec<Vec<u8>>> = None;
+    let mut pos = 0;
+    while pos < data.len() {
+        match data[pos] {
+            b'<' if data.get(pos + 1) != Some(&b'<') => {
+                let end = data[pos..].iter().position(|&b| b == b'>').map_or(data.len(), |end| pos + end);
+                let hex = parse_hex(&data[pos + 1..end]);
+                match array.as_mut() {
+                    Some(array) => array.push(hex),
+                    None => tokens.push(CMapToken::Hex(hex)),
+                }
+                pos = end + 1;
+            }
+            b'[' => {
+                array = Some(vec![]);
+                pos += 1;
+            }
+            b']' => {
+                tokens.extend(array.take().map(CMapToken::Array));
+                pos += 1;
+            }
+            b'%' => {
+                pos = data[pos..].iter().position(|&b| b == b'\n' || b == b'\r').map_or(data.len(), |end| pos + end);
+            }
+            b if b.is_ascii_alphabetic() => {
+                let end = data[pos..].iter().position(|b| !b.is_ascii_alphanumeric()).map_or(data.len(), |end| pos + end);
+                tokens.push(CMapToken::Keyword(String::from_utf8_lossy(&data[pos..end]).into_owned()));
+                pos = end;
+            }
+            _ => pos += 1,
+        }
+    }
+    tokens
+}
+
+fn parse_hex(hex: &[u8]) -> Vec<u8> {
+    let digits: Vec<u8> = hex.iter().filter_map(|&b| (b as char).to_digit(16).map(|d| d as u8)).collect();
+    // 奇数位时末位补 0
+    digits.chunks(2).map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0)).collect()
+}
+
+fn be_number(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |n, &b| n << 8 | b as u32)
+}
+
+/// Add `offset` to the last byte pair of a UTF-16BE string, as bfrange targets count.
+fn increment(bytes: &[u8], offset: u32) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    let len = bytes.len();
+    if len >= 2 {
+        let last = u16::from_be_bytes([bytes[len - 2], bytes[len - 1]]).wrapping_add(offset as u16);
+        bytes[len - 2..].copy_from_slice(&last.to_be_bytes());
+    } else if len == 1 {
+        bytes[0] = bytes[0].wrapping_add(offset as u8);
+    }
+    bytes
+}
+
+pub fn utf16_be(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes.chunks(2).map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_text_string, glyph_to_char, is_unicode_cmap, SimpleEncoding, ToUnicode};
+
+    #[test]
+    fn text_strings() {
+        // UTF-16BE with BOM
+        assert_eq!(decode_text_string(&[0xFE, 0xFF, 0x7B, 0x2C, 0x00, 0x31, 0x7A, 0xE0, 0xD8, 0x3D, 0xDE, 0x00]), "第1章😀");
+        // UTF-8 with BOM (PDF 2.0)
+        assert_eq!(decode_text_string(b"\xEF\xBB\xBFna\xC3\xAFve"), "naïve");
+        // PDFDocEncoding: bullet, em dash, fi ligature, Euro and Latin-1
+        assert_eq!(decode_text_string(b"\x80 A\x84B \x93x \xA0 caf\xE9"), "• A—B ﬁx € café");
+        // GBK bytes without BOM
+        assert_eq!(decode_text_string(&[0xB5, 0xDA, 0xD2, 0xBB, 0xD5, 0xC2, 0x20, 0xB8, 0xC5, 0xCA, 0xF6]), "第一章 概述");
+    }
+
+    #[test]
+    fn glyph_names() {
+        assert_eq!(glyph_to_char("fi"), Some('ﬁ'));
+        assert_eq!(glyph_to_char("quotedblright"), Some('”'));
+        assert_eq!(glyph_to_char("uni4E2D"), Some('中'));
+        assert_eq!(glyph_to_char("u1F600"), Some('😀'));
+        assert_eq!(glyph_to_char("a.sc"), Some('a'));
+        assert_eq!(glyph_to_char("uni4E2D.vert"), Some('中'));
+        assert_eq!(glyph_to_char(".notdef"), None);
+        assert_eq!(glyph_to_char("g123"), None);
+    }
+
+    #[test]
+    fn differences() {
+        let encoding = SimpleEncoding::new("WinAnsiEncoding", &[(1, "fi"), (2, "fl"), (0x41, "Aring")]);
+        assert_eq!(encoding.decode(b"\x01nd \x02ow A \x93q\x94"), "ﬁnd ﬂow Å “q”");
+        let standard = SimpleEncoding::new("StandardEncoding", &[]);
+        // StandardEncoding 0x27 为右单引号, 0xAE 为 fi
+        assert_eq!(standard.decode(b"it\x27s \xAEne"), "it’s ﬁne");
+    }
+
+    #[test]
+    fn unicode_cmaps() {
+        assert!(is_unicode_cmap("UniGB-UCS2-H"));
+        assert!(is_unicode_cmap("UniJIS-UTF16-V"));
+        assert!(!is_unicode_cmap("Identity-H"));
+    }
+
+    #[test]
+    fn to_unicode_cmap() {
+        let cmap = ToUnicode::parse(b"/CIDInit /ProcSet findresource begin
+            1 begincodespacerange <0000> <FFFF> endcodespacerange
+            2 beginbfchar <0003> <0020> <0011> <4E2D> endbfchar
+            2 beginbfrange <0020> <0022> <0041> <0030> <0031> [<6587> <D83DDE00>] endbfrange
+            endcmap");
+        assert_eq!(cmap.decode(&[0x00, 0x11, 0x00, 0x31, 0x00, 0x03, 0x00, 0x20, 0x00, 0x22, 0x00, 0x30, 0x01, 0x00]), "中😀 AC文");
     }
-    bail!("decode error")
-}
\ No newline at end of file
+}
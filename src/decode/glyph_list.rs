@@ -0,0 +1,262 @@
+//! Adobe Glyph List names of Latin, Greek, punctuation, symbol and ligature glyphs,
+//! the ones simple fonts name in their /Differences. Sorted by name for binary search.
+
+pub const GLYPHS: &[(&str, char)] = &[
+    ("A", '\u{41}'), ("AE", '\u{c6}'), ("AEacute", '\u{1fc}'), ("AEmacron", '\u{1e2}'),
+    ("Aacute", '\u{c1}'), ("Abreve", '\u{102}'), ("Acaron", '\u{1cd}'), ("Acircumflex", '\u{c2}'),
+    ("Adblgrave", '\u{200}'), ("Adieresis", '\u{c4}'), ("Adieresismacron", '\u{1de}'), ("Adotmacron", '\u{1e0}'),
+    ("Agrave", '\u{c0}'), ("Ainvertedbreve", '\u{202}'), ("Alpha", '\u{391}'), ("Alphatonos", '\u{386}'),
+    ("Amacron", '\u{100}'), ("Aogonek", '\u{104}'), ("Aring", '\u{c5}'), ("Aringacute", '\u{1fa}'),
+    ("Atilde", '\u{c3}'), ("B", '\u{42}'), ("Beta", '\u{392}'), ("Bhook", '\u{181}'),
+    ("Btopbar", '\u{182}'), ("C", '\u{43}'), ("Cacute", '\u{106}'), ("Ccaron", '\u{10c}'),
+    ("Ccedilla", '\u{c7}'), ("Ccircumflex", '\u{108}'), ("Cdot", '\u{10a}'), ("Cdotaccent", '\u{10a}'),
+    ("Chi", '\u{3a7}'), ("Chook", '\u{187}'), ("D", '\u{44}'), ("DZ", '\u{1f1}'),
+    ("DZcaron", '\u{1c4}'), ("Dafrican", '\u{189}'), ("Dbar", '\u{110}'), ("Dcaron", '\u{10e}'),
+    ("Dcroat", '\u{110}'), ("Deicoptic", '\u{3ee}'), ("Delta", '\u{2206}'), ("Deltagreek", '\u{394}'),
+    ("Dhook", '\u{18a}'), ("Digammagreek", '\u{3dc}'), ("Dslash", '\u{110}'), ("Dtopbar", '\u{18b}'),
+    ("Dz", '\u{1f2}'), ("Dzcaron", '\u{1c5}'), ("E", '\u{45}'), ("Eacute", '\u{c9}'),
+    ("Ebreve", '\u{114}'), ("Ecaron", '\u{11a}'), ("Ecircumflex", '\u{ca}'), ("Edblgrave", '\u{204}'),
+    ("Edieresis", '\u{cb}'), ("Edot", '\u{116}'), ("Edotaccent", '\u{116}'), ("Egrave", '\u{c8}'),
+    ("Eightroman", '\u{2167}'), ("Einvertedbreve", '\u{206}'), ("Elevenroman", '\u{216a}'), ("Emacron", '\u{112}'),
+    ("Eng", '\u{14a}'), ("Eogonek", '\u{118}'), ("Eopen", '\u{190}'), ("Epsilon", '\u{395}'),
+    ("Epsilontonos", '\u{388}'), ("Ereversed", '\u{18e}'), ("Esh", '\u{1a9}'), ("Eta", '\u{397}'),
+    ("Etatonos", '\u{389}'), ("Eth", '\u{d0}'), ("Euro", '\u{20ac}'), ("Ezh", '\u{1b7}'),
+    ("Ezhcaron", '\u{1ee}'), ("Ezhreversed", '\u{1b8}'), ("F", '\u{46}'), ("Feicoptic", '\u{3e4}'),
+    ("Fhook", '\u{191}'), ("Fiveroman", '\u{2164}'), ("Fourroman", '\u{2163}'), ("G", '\u{47}'),
+    ("Gacute", '\u{1f4}'), ("Gamma", '\u{393}'), ("Gammaafrican", '\u{194}'), ("Gangiacoptic", '\u{3ea}'),
+    ("Gbreve", '\u{11e}'), ("Gcaron", '\u{1e6}'), ("Gcedilla", '\u{122}'), ("Gcircumflex", '\u{11c}'),
+    ("Gcommaaccent", '\u{122}'), ("Gdot", '\u{120}'), ("Gdotaccent", '\u{120}'), ("Germandbls", '\u{53}'),
+    ("Ghook", '\u{193}'), ("Gstroke", '\u{1e4}'), ("H", '\u{48}'), ("Hbar", '\u{126}'),
+    ("Hcircumflex", '\u{124}'), ("Horicoptic", '\u{3e8}'), ("I", '\u{49}'), ("IJ", '\u{132}'),
+    ("Iacute", '\u{cd}'), ("Ibreve", '\u{12c}'), ("Icaron", '\u{1cf}'), ("Icircumflex", '\u{ce}'),
+    ("Idblgrave", '\u{208}'), ("Idieresis", '\u{cf}'), ("Idot", '\u{130}'), ("Idotaccent", '\u{130}'),
+    ("Ifractur", '\u{2111}'), ("Ifraktur", '\u{2111}'), ("Igrave", '\u{cc}'), ("Iinvertedbreve", '\u{20a}'),
+    ("Imacron", '\u{12a}'), ("Iogonek", '\u{12e}'), ("Iota", '\u{399}'), ("Iotaafrican", '\u{196}'),
+    ("Iotadieresis", '\u{3aa}'), ("Iotatonos", '\u{38a}'), ("Istroke", '\u{197}'), ("Itilde", '\u{128}'),
+    ("J", '\u{4a}'), ("Jcircumflex", '\u{134}'), ("K", '\u{4b}'), ("Kappa", '\u{39a}'),
+    ("Kcaron", '\u{1e8}'), ("Kcedilla", '\u{136}'), ("Kcommaaccent", '\u{136}'), ("Kheicoptic", '\u{3e6}'),
+    ("Khook", '\u{198}'), ("Koppagreek", '\u{3de}'), ("L", '\u{4c}'), ("LJ", '\u{1c7}'),
+    ("Lacute", '\u{139}'), ("Lambda", '\u{39b}'), ("Lcaron", '\u{13d}'), ("Lcedilla", '\u{13b}'),
+    ("Lcommaaccent", '\u{13b}'), ("Ldot", '\u{13f}'), ("Ldotaccent", '\u{13f}'), ("Lj", '\u{1c8}'),
+    ("Lslash", '\u{141}'), ("M", '\u{4d}'), ("Mturned", '\u{19c}'), ("Mu", '\u{39c}'),
+    ("N", '\u{4e}'), ("NJ", '\u{1ca}'), ("Nacute", '\u{143}'), ("Ncaron", '\u{147}'),
+    ("Ncedilla", '\u{145}'), ("Ncommaaccent", '\u{145}'), ("Ng", '\u{14a}'), ("Nhookleft", '\u{19d}'),
+    ("Nineroman", '\u{2168}'), ("Nj", '\u{1cb}'), ("Ntilde", '\u{d1}'), ("Nu", '\u{39d}'),
+    ("O", '\u{4f}'), ("OE", '\u{152}'), ("Oacute", '\u{d3}'), ("Obreve", '\u{14e}'),
+    ("Ocaron", '\u{1d1}'), ("Ocenteredtilde", '\u{19f}'), ("Ocircumflex", '\u{d4}'), ("Odblacute", '\u{150}'),
+    ("Odblgrave", '\u{20c}'), ("Odieresis", '\u{d6}'), ("Ograve", '\u{d2}'), ("Ohm", '\u{2126}'),
+    ("Ohorn", '\u{1a0}'), ("Ohungarumlaut", '\u{150}'), ("Oi", '\u{1a2}'), ("Oinvertedbreve", '\u{20e}'),
+    ("Omacron", '\u{14c}'), ("Omega", '\u{2126}'), ("Omegagreek", '\u{3a9}'), ("Omegatonos", '\u{38f}'),
+    ("Omicron", '\u{39f}'), ("Omicrontonos", '\u{38c}'), ("Oneroman", '\u{2160}'), ("Oogonek", '\u{1ea}'),
+    ("Oogonekmacron", '\u{1ec}'), ("Oopen", '\u{186}'), ("Oslash", '\u{d8}'), ("Oslashacute", '\u{1fe}'),
+    ("Ostrokeacute", '\u{1fe}'), ("Otilde", '\u{d5}'), ("P", '\u{50}'), ("Phi", '\u{3a6}'),
+    ("Phook", '\u{1a4}'), ("Pi", '\u{3a0}'), ("Psi", '\u{3a8}'), ("Q", '\u{51}'),
+    ("R", '\u{52}'), ("Racute", '\u{154}'), ("Rcaron", '\u{158}'), ("Rcedilla", '\u{156}'),
+    ("Rcommaaccent", '\u{156}'), ("Rdblgrave", '\u{210}'), ("Rfractur", '\u{211c}'), ("Rfraktur", '\u{211c}'),
+    ("Rho", '\u{3a1}'), ("Rinvertedbreve", '\u{212}'), ("S", '\u{53}'), ("SS", '\u{53}'),
+    ("Sacute", '\u{15a}'), ("Sampigreek", '\u{3e0}'), ("Scaron", '\u{160}'), ("Scedilla", '\u{15e}'),
+    ("Schwa", '\u{18f}'), ("Scircumflex", '\u{15c}'), ("Scommaaccent", '\u{218}'), ("Sevenroman", '\u{2166}'),
+    ("Sheicoptic", '\u{3e2}'), ("Shimacoptic", '\u{3ec}'), ("Sigma", '\u{3a3}'), ("Sixroman", '\u{2165}'),
+    ("Stigmagreek", '\u{3da}'), ("T", '\u{54}'), ("Tau", '\u{3a4}'), ("Tbar", '\u{166}'),
+    ("Tcaron", '\u{164}'), ("Tcedilla", '\u{162}'), ("Tcommaaccent", '\u{162}'), ("Tenroman", '\u{2169}'),
+    ("Theta", '\u{398}'), ("Thook", '\u{1ac}'), ("Thorn", '\u{de}'), ("Threeroman", '\u{2162}'),
+    ("Tonefive", '\u{1bc}'), ("Tonesix", '\u{184}'), ("Tonetwo", '\u{1a7}'), ("Tretroflexhook", '\u{1ae}'),
+    ("Twelveroman", '\u{216b}'), ("Tworoman", '\u{2161}'), ("U", '\u{55}'), ("Uacute", '\u{da}'),
+    ("Ubreve", '\u{16c}'), ("Ucaron", '\u{1d3}'), ("Ucircumflex", '\u{db}'), ("Udblacute", '\u{170}'),
+    ("Udblgrave", '\u{214}'), ("Udieresis", '\u{dc}'), ("Udieresisacute", '\u{1d7}'), ("Udieresiscaron", '\u{1d9}'),
+    ("Udieresisgrave", '\u{1db}'), ("Udieresismacron", '\u{1d5}'), ("Ugrave", '\u{d9}'), ("Uhorn", '\u{1af}'),
+    ("Uhungarumlaut", '\u{170}'), ("Uinvertedbreve", '\u{216}'), ("Umacron", '\u{16a}'), ("Uogonek", '\u{172}'),
+    ("Upsilon", '\u{3a5}'), ("Upsilon1", '\u{3d2}'), ("Upsilonacutehooksymbolgreek", '\u{3d3}'), ("Upsilonafrican", '\u{1b1}'),
+    ("Upsilondieresis", '\u{3ab}'), ("Upsilondieresishooksymbolgreek", '\u{3d4}'), ("Upsilonhooksymbol", '\u{3d2}'), ("Upsilontonos", '\u{38e}'),
+    ("Uring", '\u{16e}'), ("Utilde", '\u{168}'), ("V", '\u{56}'), ("Vhook", '\u{1b2}'),
+    ("W", '\u{57}'), ("Wcircumflex", '\u{174}'), ("X", '\u{58}'), ("Xi", '\u{39e}'),
+    ("Y", '\u{59}'), ("Yacute", '\u{dd}'), ("Ycircumflex", '\u{176}'), ("Ydieresis", '\u{178}'),
+    ("Yhook", '\u{1b3}'), ("Z", '\u{5a}'), ("Zacute", '\u{179}'), ("Zcaron", '\u{17d}'),
+    ("Zdot", '\u{17b}'), ("Zdotaccent", '\u{17b}'), ("Zeta", '\u{396}'), ("Zstroke", '\u{1b5}'),
+    ("a", '\u{61}'), ("aacute", '\u{e1}'), ("abreve", '\u{103}'), ("acaron", '\u{1ce}'),
+    ("acircumflex", '\u{e2}'), ("acute", '\u{b4}'), ("adblgrave", '\u{201}'), ("adieresis", '\u{e4}'),
+    ("adieresismacron", '\u{1df}'), ("adotmacron", '\u{1e1}'), ("ae", '\u{e6}'), ("aeacute", '\u{1fd}'),
+    ("aemacron", '\u{1e3}'), ("afii00208", '\u{2015}'), ("afii08941", '\u{20a4}'), ("afii299", '\u{200e}'),
+    ("afii300", '\u{200f}'), ("afii301", '\u{200d}'), ("afii57636", '\u{20aa}'), ("afii61248", '\u{2105}'),
+    ("afii61289", '\u{2113}'), ("afii61352", '\u{2116}'), ("afii61573", '\u{202c}'), ("afii61574", '\u{202d}'),
+    ("afii61575", '\u{202e}'), ("afii61664", '\u{200c}'), ("agrave", '\u{e0}'), ("ainvertedbreve", '\u{203}'),
+    ("aleph", '\u{2135}'), ("allequal", '\u{224c}'), ("alpha", '\u{3b1}'), ("alphatonos", '\u{3ac}'),
+    ("amacron", '\u{101}'), ("ampersand", '\u{26}'), ("angle", '\u{2220}'), ("angstrom", '\u{212b}'),
+    ("anoteleia", '\u{387}'), ("aogonek", '\u{105}'), ("approaches", '\u{2250}'), ("approxequal", '\u{2248}'),
+    ("approxequalorimage", '\u{2252}'), ("approximatelyequal", '\u{2245}'), ("aring", '\u{e5}'), ("aringacute", '\u{1fb}'),
+    ("arrowboth", '\u{2194}'), ("arrowbothv", '\u{2195}'), ("arrowbt", '\u{2193}'), ("arrowdashdown", '\u{21e3}'),
+    ("arrowdashleft", '\u{21e0}'), ("arrowdashright", '\u{21e2}'), ("arrowdashup", '\u{21e1}'), ("arrowdblboth", '\u{21d4}'),
+    ("arrowdblbothv", '\u{21d5}'), ("arrowdbldown", '\u{21d3}'), ("arrowdblleft", '\u{21d0}'), ("arrowdblright", '\u{21d2}'),
+    ("arrowdbltp", '\u{21d1}'), ("arrowdblup", '\u{21d1}'), ("arrowdblvertex", '\u{21d5}'), ("arrowdown", '\u{2193}'),
+    ("arrowdownleft", '\u{2199}'), ("arrowdownright", '\u{2198}'), ("arrowdownwhite", '\u{21e9}'), ("arrowleft", '\u{2190}'),
+    ("arrowleftbothalf", '\u{21bd}'), ("arrowleftdbl", '\u{21d0}'), ("arrowleftdblstroke", '\u{21cd}'), ("arrowleftoverright", '\u{21c6}'),
+    ("arrowlefttophalf", '\u{21bc}'), ("arrowleftwhite", '\u{21e6}'), ("arrownortheast", '\u{2197}'), ("arrownorthwest", '\u{2196}'),
+    ("arrowright", '\u{2192}'), ("arrowrightbothalf", '\u{21c1}'), ("arrowrightdblstroke", '\u{21cf}'), ("arrowrightoverleft", '\u{21c4}'),
+    ("arrowrighttophalf", '\u{21c0}'), ("arrowrightwhite", '\u{21e8}'), ("arrowsoutheast", '\u{2198}'), ("arrowsouthwest", '\u{2199}'),
+    ("arrowtableft", '\u{21e4}'), ("arrowtabright", '\u{21e5}'), ("arrowtp", '\u{2191}'), ("arrowup", '\u{2191}'),
+    ("arrowupdn", '\u{2195}'), ("arrowupdnbse", '\u{21a8}'), ("arrowupdownbase", '\u{21a8}'), ("arrowupleft", '\u{2196}'),
+    ("arrowupleftofdown", '\u{21c5}'), ("arrowupright", '\u{2197}'), ("arrowupwhite", '\u{21e7}'), ("arrowvertex", '\u{2195}'),
+    ("asciicircum", '\u{5e}'), ("asciitilde", '\u{7e}'), ("asterisk", '\u{2a}'), ("asteriskcentered", '\u{2217}'),
+    ("asteriskmath", '\u{2217}'), ("asterism", '\u{2042}'), ("asymptoticallyequal", '\u{2243}'), ("at", '\u{40}'),
+    ("atilde", '\u{e3}'), ("b", '\u{62}'), ("backslash", '\u{5c}'), ("backslashBig", '\u{5c}'),
+    ("backslashBigg", '\u{5c}'), ("backslashbig", '\u{5c}'), ("backslashbigg", '\u{5c}'), ("bar", '\u{7c}'),
+    ("bardbl", '\u{2225}'), ("bardblex", '\u{2016}'), ("barex", '\u{7c}'), ("because", '\u{2235}'),
+    ("beta", '\u{3b2}'), ("betasymbolgreek", '\u{3d0}'), ("braceex", '\u{7c}'), ("braceleft", '\u{7b}'),
+    ("braceleftBig", '\u{7b}'), ("braceleftBigg", '\u{7b}'), ("braceleftbig", '\u{7b}'), ("braceleftbigg", '\u{7b}'),
+    ("braceleftmid", '\u{7c}'), ("braceright", '\u{7d}'), ("bracerightBig", '\u{7d}'), ("bracerightBigg", '\u{7d}'),
+    ("bracerightbig", '\u{7d}'), ("bracerightbigg", '\u{7d}'), ("bracerightmid", '\u{2016}'), ("bracketleft", '\u{5b}'),
+    ("bracketleftBig", '\u{5b}'), ("bracketleftBigg", '\u{5b}'), ("bracketleftbig", '\u{5b}'), ("bracketleftbigg", '\u{5b}'),
+    ("bracketright", '\u{5d}'), ("bracketrightBig", '\u{5d}'), ("bracketrightBigg", '\u{5d}'), ("bracketrightbig", '\u{5d}'),
+    ("bracketrightbigg", '\u{5d}'), ("brokenbar", '\u{a6}'), ("bstroke", '\u{180}'), ("btopbar", '\u{183}'),
+    ("bullet", '\u{2022}'), ("bulletoperator", '\u{2219}'), ("c", '\u{63}'), ("cacute", '\u{107}'),
+    ("capslock", '\u{21ea}'), ("careof", '\u{2105}'), ("carriagereturn", '\u{21b5}'), ("ccaron", '\u{10d}'),
+    ("ccedilla", '\u{e7}'), ("ccircumflex", '\u{109}'), ("cdot", '\u{10b}'), ("cdotaccent", '\u{10b}'),
+    ("cedilla", '\u{b8}'), ("cent", '\u{a2}'), ("centigrade", '\u{2103}'), ("chi", '\u{3c7}'),
+    ("chook", '\u{188}'), ("circledivide", '\u{2298}'), ("circledot", '\u{2299}'), ("circledotdisplay", '\u{2299}'),
+    ("circledottext", '\u{2299}'), ("circleminus", '\u{2296}'), ("circlemultiply", '\u{2297}'), ("circlemultiplydisplay", '\u{2297}'),
+    ("circlemultiplytext", '\u{2297}'), ("circleot", '\u{2299}'), ("circleplus", '\u{2295}'), ("circleplusdisplay", '\u{2295}'),
+    ("circleplustext", '\u{2295}'), ("clickalveolar", '\u{1c2}'), ("clickdental", '\u{1c0}'), ("clicklateral", '\u{1c1}'),
+    ("clickretroflex", '\u{1c3}'), ("colon", '\u{3a}'), ("colonmonetary", '\u{20a1}'), ("colonsign", '\u{20a1}'),
+    ("comma", '\u{2c}'), ("compwordmark", '\u{200c}'), ("congruent", '\u{2245}'), ("contintegraldisplay", '\u{222e}'),
+    ("contintegraltext", '\u{222e}'), ("contourintegral", '\u{222e}'), ("controlDEL", '\u{7f}'), ("coproductdisplay", '\u{2210}'),
+    ("coproducttext", '\u{2210}'), ("copyright", '\u{a9}'), ("cruzeiro", '\u{20a2}'), ("curlyand", '\u{22cf}'),
+    ("curlyor", '\u{22ce}'), ("currency", '\u{a4}'), ("cwm", '\u{200c}'), ("d", '\u{64}'),
+    ("dagger", '\u{2020}'), ("daggerdbl", '\u{2021}'), ("dbar", '\u{111}'), ("dblarrowleft", '\u{21d4}'),
+    ("dblarrowright", '\u{21d2}'), ("dblintegral", '\u{222c}'), ("dbllowline", '\u{2017}'), ("dblverticalbar", '\u{2016}'),
+    ("dcaron", '\u{10f}'), ("dcroat", '\u{111}'), ("degree", '\u{b0}'), ("deicoptic", '\u{3ef}'),
+    ("delta", '\u{3b4}'), ("deltaturned", '\u{18d}'), ("dialytikatonos", '\u{385}'), ("diamondmath", '\u{22c4}'),
+    ("dieresis", '\u{a8}'), ("dieresistonos", '\u{385}'), ("divide", '\u{f7}'), ("divides", '\u{2223}'),
+    ("divisionslash", '\u{2215}'), ("dmacron", '\u{111}'), ("dollar", '\u{24}'), ("dong", '\u{20ab}'),
+    ("dotlessi", '\u{131}'), ("dotlessj", '\u{237}'), ("dotmath", '\u{22c5}'), ("dtopbar", '\u{18c}'),
+    ("dz", '\u{1f3}'), ("dzcaron", '\u{1c6}'), ("e", '\u{65}'), ("eacute", '\u{e9}'),
+    ("ebreve", '\u{115}'), ("ecaron", '\u{11b}'), ("ecircumflex", '\u{ea}'), ("edblgrave", '\u{205}'),
+    ("edieresis", '\u{eb}'), ("edot", '\u{117}'), ("edotaccent", '\u{117}'), ("egrave", '\u{e8}'),
+    ("eight", '\u{38}'), ("eightroman", '\u{2177}'), ("einvertedbreve", '\u{207}'), ("element", '\u{2208}'),
+    ("elevenroman", '\u{217a}'), ("ellipsis", '\u{2026}'), ("ellipsisvertical", '\u{22ee}'), ("emacron", '\u{113}'),
+    ("emdash", '\u{2014}'), ("emptyset", '\u{2205}'), ("endash", '\u{2013}'), ("eng", '\u{14b}'),
+    ("enspace", '\u{2002}'), ("eogonek", '\u{119}'), ("epsilon", '\u{3b5}'), ("epsilon1", '\u{3f5}'),
+    ("epsilontonos", '\u{3ad}'), ("equal", '\u{3d}'), ("equivalence", '\u{2261}'), ("equivasymptotic", '\u{224d}'),
+    ("eshreversedloop", '\u{1aa}'), ("estimated", '\u{212e}'), ("eta", '\u{3b7}'), ("etatonos", '\u{3ae}'),
+    ("eth", '\u{f0}'), ("eturned", '\u{1dd}'), ("euro", '\u{20ac}'), ("exclam", '\u{21}'),
+    ("exclamdbl", '\u{203c}'), ("exclamdown", '\u{a1}'), ("existential", '\u{2203}'), ("ezhcaron", '\u{1ef}'),
+    ("ezhreversed", '\u{1b9}'), ("ezhtail", '\u{1ba}'), ("f", '\u{66}'), ("f_f", '\u{fb00}'),
+    ("f_f_i", '\u{fb03}'), ("f_f_l", '\u{fb04}'), ("f_i", '\u{fb01}'), ("f_l", '\u{fb02}'),
+    ("fahrenheit", '\u{2109}'), ("feicoptic", '\u{3e5}'), ("ff", '\u{fb00}'), ("ffi", '\u{fb03}'),
+    ("ffl", '\u{fb04}'), ("fi", '\u{fb01}'), ("figuredash", '\u{2012}'), ("five", '\u{35}'),
+    ("fiveeighths", '\u{215d}'), ("fiveroman", '\u{2174}'), ("fl", '\u{fb02}'), ("florin", '\u{192}'),
+    ("follows", '\u{227b}'), ("followsequal", '\u{227d}'), ("forall", '\u{2200}'), ("four", '\u{34}'),
+    ("fourroman", '\u{2173}'), ("fraction", '\u{2044}'), ("franc", '\u{20a3}'), ("g", '\u{67}'),
+    ("gacute", '\u{1f5}'), ("gamma", '\u{3b3}'), ("gangiacoptic", '\u{3eb}'), ("gbreve", '\u{11f}'),
+    ("gcaron", '\u{1e7}'), ("gcedilla", '\u{123}'), ("gcircumflex", '\u{11d}'), ("gcommaaccent", '\u{123}'),
+    ("gdot", '\u{121}'), ("gdotaccent", '\u{121}'), ("geometricallyequal", '\u{2251}'), ("germandbls", '\u{df}'),
+    ("glottalinvertedstroke", '\u{1be}'), ("gradient", '\u{2207}'), ("grave", '\u{60}'), ("greater", '\u{3e}'),
+    ("greaterequal", '\u{2265}'), ("greaterequalorless", '\u{22db}'), ("greatermuch", '\u{226b}'), ("greaterorequivalent", '\u{2273}'),
+    ("greaterorless", '\u{2277}'), ("greateroverequal", '\u{2267}'), ("gstroke", '\u{1e5}'), ("guillemotleft", '\u{ab}'),
+    ("guillemotright", '\u{bb}'), ("guilsinglleft", '\u{2039}'), ("guilsinglright", '\u{203a}'), ("h", '\u{68}'),
+    ("harpoonleftbarbup", '\u{21bc}'), ("harpoonleftdown", '\u{21bd}'), ("harpoonleftup", '\u{21bc}'), ("harpoonrightbarbup", '\u{21c0}'),
+    ("harpoonrightdown", '\u{21c1}'), ("harpoonrightup", '\u{21c0}'), ("hbar", '\u{127}'), ("hcircumflex", '\u{125}'),
+    ("hookleftchar", '\u{21a9}'), ("hookrightchar", '\u{21aa}'), ("horicoptic", '\u{3e9}'), ("horizontalbar", '\u{2015}'),
+    ("hv", '\u{195}'), ("hyphen", '\u{2d}'), ("hyphen_alt", '\u{2010}'), ("hyphenchar", '\u{2d}'),
+    ("hyphentwo", '\u{2010}'), ("i", '\u{69}'), ("iacute", '\u{ed}'), ("ibreve", '\u{12d}'),
+    ("icaron", '\u{1d0}'), ("icircumflex", '\u{ee}'), ("idblgrave", '\u{209}'), ("idieresis", '\u{ef}'),
+    ("igrave", '\u{ec}'), ("iinvertedbreve", '\u{20b}'), ("ij", '\u{133}'), ("imacron", '\u{12b}'),
+    ("imageorapproximatelyequal", '\u{2253}'), ("increment", '\u{2206}'), ("infinity", '\u{221e}'), ("integral", '\u{222b}'),
+    ("integraldisplay", '\u{222b}'), ("integraltext", '\u{222b}'), ("interrobang", '\u{203d}'), ("intersection", '\u{2229}'),
+    ("intersectiondisplay", '\u{22c2}'), ("intersectionsq", '\u{2293}'), ("intersectiontext", '\u{22c2}'), ("iogonek", '\u{12f}'),
+    ("iota", '\u{3b9}'), ("iotadieresis", '\u{3ca}'), ("iotadieresistonos", '\u{390}'), ("iotatonos", '\u{3af}'),
+    ("itilde", '\u{129}'), ("j", '\u{6a}'), ("jcaron", '\u{1f0}'), ("jcircumflex", '\u{135}'),
+    ("k", '\u{6b}'), ("kappa", '\u{3ba}'), ("kappasymbolgreek", '\u{3f0}'), ("kcaron", '\u{1e9}'),
+    ("kcedilla", '\u{137}'), ("kcommaaccent", '\u{137}'), ("kgreenlandic", '\u{138}'), ("kheicoptic", '\u{3e7}'),
+    ("khook", '\u{199}'), ("l", '\u{6c}'), ("lacute", '\u{13a}'), ("lambda", '\u{3bb}'),
+    ("lambdastroke", '\u{19b}'), ("latticetop", '\u{22a4}'), ("lbar", '\u{19a}'), ("lcaron", '\u{13e}'),
+    ("lcedilla", '\u{13c}'), ("lcommaaccent", '\u{13c}'), ("ldot", '\u{140}'), ("ldotaccent", '\u{140}'),
+    ("less", '\u{3c}'), ("lessequal", '\u{2264}'), ("lessequalorgreater", '\u{22da}'), ("lessmuch", '\u{226a}'),
+    ("lessorequivalent", '\u{2272}'), ("lessorgreater", '\u{2276}'), ("lessoverequal", '\u{2266}'), ("lira", '\u{20a4}'),
+    ("lj", '\u{1c9}'), ("logicaland", '\u{2227}'), ("logicalanddisplay", '\u{22c0}'), ("logicalandtext", '\u{22c0}'),
+    ("logicalnot", '\u{ac}'), ("logicalor", '\u{2228}'), ("logicalordisplay", '\u{22c1}'), ("logicalortext", '\u{22c1}'),
+    ("longs", '\u{17f}'), ("lscript", '\u{2113}'), ("lslash", '\u{142}'), ("lsquare", '\u{2113}'),
+    ("m", '\u{6d}'), ("macron", '\u{af}'), ("middot", '\u{b7}'), ("minus", '\u{2212}'),
+    ("minuscircle", '\u{2296}'), ("minusplus", '\u{2213}'), ("minute", '\u{2032}'), ("mu", '\u{b5}'),
+    ("mu1", '\u{b5}'), ("muchgreater", '\u{226b}'), ("muchless", '\u{226a}'), ("mugreek", '\u{3bc}'),
+    ("multiply", '\u{d7}'), ("n", '\u{6e}'), ("nabla", '\u{2207}'), ("nacute", '\u{144}'),
+    ("napostrophe", '\u{149}'), ("nbspace", '\u{a0}'), ("ncaron", '\u{148}'), ("ncedilla", '\u{146}'),
+    ("ncommaaccent", '\u{146}'), ("newsheqelsign", '\u{20aa}'), ("ng", '\u{14b}'), ("nine", '\u{39}'),
+    ("nineroman", '\u{2178}'), ("nj", '\u{1cc}'), ("nlegrightlong", '\u{19e}'), ("nonbreakingspace", '\u{a0}'),
+    ("notcontains", '\u{220c}'), ("notelement", '\u{2209}'), ("notelementof", '\u{2209}'), ("notequal", '\u{2260}'),
+    ("notgreater", '\u{226f}'), ("notgreaternorequal", '\u{2271}'), ("notgreaternorless", '\u{2279}'), ("notidentical", '\u{2262}'),
+    ("notless", '\u{226e}'), ("notlessnorequal", '\u{2270}'), ("notparallel", '\u{2226}'), ("notprecedes", '\u{2280}'),
+    ("notsubset", '\u{2284}'), ("notsucceeds", '\u{2281}'), ("notsuperset", '\u{2285}'), ("ntilde", '\u{f1}'),
+    ("nu", '\u{3bd}'), ("numbersign", '\u{23}'), ("numeralsigngreek", '\u{374}'), ("numeralsignlowergreek", '\u{375}'),
+    ("numero", '\u{2116}'), ("o", '\u{6f}'), ("oacute", '\u{f3}'), ("obreve", '\u{14f}'),
+    ("ocaron", '\u{1d2}'), ("ocircumflex", '\u{f4}'), ("odblacute", '\u{151}'), ("odblgrave", '\u{20d}'),
+    ("odieresis", '\u{f6}'), ("oe", '\u{153}'), ("ograve", '\u{f2}'), ("ohorn", '\u{1a1}'),
+    ("ohungarumlaut", '\u{151}'), ("oi", '\u{1a3}'), ("oinvertedbreve", '\u{20f}'), ("omacron", '\u{14d}'),
+    ("omega", '\u{3c9}'), ("omega1", '\u{3d6}'), ("omegatonos", '\u{3ce}'), ("omicron", '\u{3bf}'),
+    ("omicrontonos", '\u{3cc}'), ("one", '\u{31}'), ("onedotenleader", '\u{2024}'), ("oneeighth", '\u{215b}'),
+    ("onehalf", '\u{bd}'), ("onequarter", '\u{bc}'), ("oneroman", '\u{2170}'), ("onesuperior", '\u{b9}'),
+    ("onethird", '\u{2153}'), ("oogonek", '\u{1eb}'), ("oogonekmacron", '\u{1ed}'), ("ordfeminine", '\u{aa}'),
+    ("ordmasculine", '\u{ba}'), ("orthogonal", '\u{221f}'), ("oslash", '\u{f8}'), ("oslashacute", '\u{1ff}'),
+    ("ostrokeacute", '\u{1ff}'), ("otilde", '\u{f5}'), ("overline", '\u{203e}'), ("overscore", '\u{af}'),
+    ("owner", '\u{220b}'), ("p", '\u{70}'), ("pagedown", '\u{21df}'), ("pageup", '\u{21de}'),
+    ("paragraph", '\u{b6}'), ("parallel", '\u{2225}'), ("parenleft", '\u{28}'), ("parenleftBig", '\u{28}'),
+    ("parenleftBigg", '\u{28}'), ("parenleftbig", '\u{28}'), ("parenleftbigg", '\u{28}'), ("parenleftex", '\u{7c}'),
+    ("parenright", '\u{29}'), ("parenrightBig", '\u{29}'), ("parenrightBigg", '\u{29}'), ("parenrightbig", '\u{29}'),
+    ("parenrightbigg", '\u{29}'), ("parenrightex", '\u{7c}'), ("partialdiff", '\u{2202}'), ("percent", '\u{25}'),
+    ("period", '\u{2e}'), ("periodcentered", '\u{b7}'), ("perpendicular", '\u{22a5}'), ("pertenthousand", '\u{2031}'),
+    ("perthousand", '\u{2030}'), ("peseta", '\u{20a7}'), ("phi", '\u{3c6}'), ("phi2", '\u{3d5}'),
+    ("phisymbolgreek", '\u{3d5}'), ("phook", '\u{1a5}'), ("pi", '\u{3c0}'), ("pi1", '\u{3d6}'),
+    ("pisymbolgreek", '\u{3d6}'), ("plus", '\u{2b}'), ("pluscircle", '\u{2295}'), ("plusminus", '\u{b1}'),
+    ("precedes", '\u{227a}'), ("precedesequal", '\u{227c}'), ("prescription", '\u{211e}'), ("prime", '\u{2032}'),
+    ("primereversed", '\u{2035}'), ("product", '\u{220f}'), ("productdisplay", '\u{220f}'), ("producttext", '\u{220f}'),
+    ("propersubset", '\u{2282}'), ("propersuperset", '\u{2283}'), ("proportion", '\u{2237}'), ("proportional", '\u{221d}'),
+    ("psi", '\u{3c8}'), ("punctdash", '\u{2014}'), ("q", '\u{71}'), ("question", '\u{3f}'),
+    ("questiondown", '\u{bf}'), ("questiongreek", '\u{37e}'), ("quotedbl", '\u{22}'), ("quotedblbase", '\u{201e}'),
+    ("quotedblleft", '\u{201c}'), ("quotedblright", '\u{201d}'), ("quoteleft", '\u{2018}'), ("quoteleftreversed", '\u{201b}'),
+    ("quotereversed", '\u{201b}'), ("quoteright", '\u{2019}'), ("quoterightn", '\u{149}'), ("quotesinglbase", '\u{201a}'),
+    ("quotesingle", '\u{27}'), ("r", '\u{72}'), ("racute", '\u{155}'), ("radical", '\u{221a}'),
+    ("radicalBig", '\u{221a}'), ("radicalBigg", '\u{221a}'), ("radicalbig", '\u{221a}'), ("radicalbigg", '\u{221a}'),
+    ("radicalbt", '\u{221a}'), ("rangedash", '\u{2013}'), ("ratio", '\u{2236}'), ("rcaron", '\u{159}'),
+    ("rcedilla", '\u{157}'), ("rcommaaccent", '\u{157}'), ("rdblgrave", '\u{211}'), ("referencemark", '\u{203b}'),
+    ("reflexsubset", '\u{2286}'), ("reflexsuperset", '\u{2287}'), ("registered", '\u{ae}'), ("reversedtilde", '\u{223d}'),
+    ("rho", '\u{3c1}'), ("rho1", '\u{3f1}'), ("rhosymbolgreek", '\u{3f1}'), ("rightangle", '\u{221f}'),
+    ("righttriangle", '\u{22bf}'), ("rinvertedbreve", '\u{213}'), ("s", '\u{73}'), ("sacute", '\u{15b}'),
+    ("scaron", '\u{161}'), ("scedilla", '\u{15f}'), ("scircumflex", '\u{15d}'), ("scommaaccent", '\u{219}'),
+    ("second", '\u{2033}'), ("section", '\u{a7}'), ("semicolon", '\u{3b}'), ("seven", '\u{37}'),
+    ("seveneighths", '\u{215e}'), ("sevenroman", '\u{2176}'), ("sfthyphen", '\u{ad}'), ("sheicoptic", '\u{3e3}'),
+    ("sheqel", '\u{20aa}'), ("sheqelhebrew", '\u{20aa}'), ("shimacoptic", '\u{3ed}'), ("sigma", '\u{3c3}'),
+    ("sigma1", '\u{3c2}'), ("sigmafinal", '\u{3c2}'), ("sigmalunatesymbolgreek", '\u{3f2}'), ("similar", '\u{223c}'),
+    ("similarequal", '\u{2243}'), ("six", '\u{36}'), ("sixroman", '\u{2175}'), ("slash", '\u{2f}'),
+    ("slashBig", '\u{2215}'), ("slashBigg", '\u{2215}'), ("slashbig", '\u{2215}'), ("slashbigg", '\u{2215}'),
+    ("slong", '\u{17f}'), ("softhyphen", '\u{ad}'), ("space", '\u{20}'), ("spacehackarabic", '\u{20}'),
+    ("star", '\u{22c6}'), ("sterling", '\u{a3}'), ("subset", '\u{2282}'), ("subsetnotequal", '\u{228a}'),
+    ("subsetorequal", '\u{2286}'), ("subsetsqequal", '\u{2291}'), ("succeeds", '\u{227b}'), ("suchthat", '\u{220b}'),
+    ("summation", '\u{2211}'), ("summationdisplay", '\u{2211}'), ("summationtext", '\u{2211}'), ("superset", '\u{2283}'),
+    ("supersetnotequal", '\u{228b}'), ("supersetorequal", '\u{2287}'), ("supersetsqequal", '\u{2292}'), ("t", '\u{74}'),
+    ("tackdown", '\u{22a4}'), ("tackleft", '\u{22a3}'), ("tau", '\u{3c4}'), ("tbar", '\u{167}'),
+    ("tcaron", '\u{165}'), ("tcedilla", '\u{163}'), ("tcommaaccent", '\u{163}'), ("telephone", '\u{2121}'),
+    ("tenroman", '\u{2179}'), ("thereexists", '\u{2203}'), ("therefore", '\u{2234}'), ("theta", '\u{3b8}'),
+    ("theta1", '\u{3d1}'), ("thetasymbolgreek", '\u{3d1}'), ("thook", '\u{1ad}'), ("thorn", '\u{fe}'),
+    ("three", '\u{33}'), ("threeeighths", '\u{215c}'), ("threequarters", '\u{be}'), ("threeroman", '\u{2172}'),
+    ("threesuperior", '\u{b3}'), ("tie", '\u{2040}'), ("tildeoperator", '\u{223c}'), ("timescircle", '\u{2297}'),
+    ("tonefive", '\u{1bd}'), ("tonesix", '\u{185}'), ("tonetwo", '\u{1a8}'), ("tonos", '\u{384}'),
+    ("tpalatalhook", '\u{1ab}'), ("trademark", '\u{2122}'), ("turnstileleft", '\u{22a2}'), ("turnstileright", '\u{22a3}'),
+    ("twelveroman", '\u{217b}'), ("two", '\u{32}'), ("twodotenleader", '\u{2025}'), ("twodotleader", '\u{2025}'),
+    ("tworoman", '\u{2171}'), ("twostroke", '\u{1bb}'), ("twosuperior", '\u{b2}'), ("twothirds", '\u{2154}'),
+    ("u", '\u{75}'), ("uacute", '\u{fa}'), ("ubreve", '\u{16d}'), ("ucaron", '\u{1d4}'),
+    ("ucircumflex", '\u{fb}'), ("udblacute", '\u{171}'), ("udblgrave", '\u{215}'), ("udieresis", '\u{fc}'),
+    ("udieresisacute", '\u{1d8}'), ("udieresiscaron", '\u{1da}'), ("udieresisgrave", '\u{1dc}'), ("udieresismacron", '\u{1d6}'),
+    ("ugrave", '\u{f9}'), ("uhorn", '\u{1b0}'), ("uhungarumlaut", '\u{171}'), ("uinvertedbreve", '\u{217}'),
+    ("umacron", '\u{16b}'), ("underscore", '\u{5f}'), ("underscoredbl", '\u{2017}'), ("union", '\u{222a}'),
+    ("uniondisplay", '\u{22c3}'), ("unionmulti", '\u{228e}'), ("unionmultidisplay", '\u{228e}'), ("unionmultitext", '\u{228e}'),
+    ("unionsq", '\u{2294}'), ("unionsqdisplay", '\u{2294}'), ("unionsqtext", '\u{2294}'), ("uniontext", '\u{22c3}'),
+    ("universal", '\u{2200}'), ("uogonek", '\u{173}'), ("upsilon", '\u{3c5}'), ("upsilondieresis", '\u{3cb}'),
+    ("upsilondieresistonos", '\u{3b0}'), ("upsilontonos", '\u{3cd}'), ("uring", '\u{16f}'), ("utilde", '\u{169}'),
+    ("v", '\u{76}'), ("verticalbar", '\u{7c}'), ("w", '\u{77}'), ("wcircumflex", '\u{175}'),
+    ("weierstrass", '\u{2118}'), ("won", '\u{20a9}'), ("wreathproduct", '\u{2240}'), ("wynn", '\u{1bf}'),
+    ("x", '\u{78}'), ("xi", '\u{3be}'), ("y", '\u{79}'), ("yacute", '\u{fd}'),
+    ("ycircumflex", '\u{177}'), ("ydieresis", '\u{ff}'), ("yen", '\u{a5}'), ("yhook", '\u{1b4}'),
+    ("yotgreek", '\u{3f3}'), ("ypogegrammeni", '\u{37a}'), ("yr", '\u{1a6}'), ("z", '\u{7a}'),
+    ("zacute", '\u{17a}'), ("zcaron", '\u{17e}'), ("zdot", '\u{17c}'), ("zdotaccent", '\u{17c}'),
+    ("zero", '\u{30}'), ("zerowidthnonjoiner", '\u{200c}'), ("zerowidthspace", '\u{200b}'), ("zeta", '\u{3b6}'),
+    ("zstroke", '\u{1b6}'),
+];
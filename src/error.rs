@@ -0,0 +1,50 @@
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+/// Errors of the pdf, page cache and history handling.
+#[derive(Debug)]
+pub enum Error {
+    /// the pdf is missing or can't be parsed
+    Pdf { path: String, source: lopdf::Error },
+    /// a file or directory of the cache or the history can't be read or written
+    Io { path: PathBuf, source: io::Error },
+    /// the rasterizer failed on a page or its output isn't an image
+    Render { page_id: u32, message: String },
+    /// no home directory to keep the history in
+    NoDataDir,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn io(path: impl Into<PathBuf>) -> impl FnOnce(io::Error) -> Self {
+        let path = path.into();
+        move |source| Error::Io { path, source }
+    }
+
+    pub fn render(page_id: u32, e: impl Display) -> Self {
+        Error::Render { page_id, message: format!("{e:#}") }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Pdf { path, source } => write!(f, "can't open {path}: {source}"),
+            Error::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            Error::Render { page_id, message } => write!(f, "page {page_id} failed to render: {message}"),
+            Error::NoDataDir => write!(f, "no home directory found for the history"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Pdf { source, .. } => Some(source),
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
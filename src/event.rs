@@ -3,7 +3,9 @@ use std::time::Duration;
 use crossterm::event::{Event as CrosstermEvent, KeyEvent};
 use futures::{FutureExt, StreamExt};
 use tokio::sync::mpsc;
+use std::sync::Arc;
 use crate::app::AppState;
+use crate::index::SearchIndex;
 use crate::ro_cell::RoCell;
 
 static TX: RoCell<mpsc::UnboundedSender<Event>> = RoCell::new();
@@ -27,6 +29,14 @@ pub enum Event {
     LoadingFirst(u32),
     /// change state
     ChangeState(AppState),
+    /// full-text index loaded or built
+    IndexReady(Arc<SearchIndex>),
+    /// wipe the screen, images included, and draw everything again
+    ClearScreen,
+    /// the rasterizer failed on a page, with the message to show
+    RenderFailed(u32, String),
+    /// the rasterizer failed on the thumbnail of a page, the page itself may still render
+    ThumbnailFailed(u32, String),
 }
 
 /// Terminal event handler.
@@ -135,4 +145,16 @@ macro_rules! emit {
     (ChangeState($state: expr)) => {
         $crate::event::Event::ChangeState($state).emit()
     };
+    (ClearScreen) => {
+        $crate::event::Event::ClearScreen.emit()
+    };
+    (IndexReady($index: expr)) => {
+        $crate::event::Event::IndexReady($index).emit()
+    };
+    (RenderFailed($page_id: expr, $message: expr)) => {
+        $crate::event::Event::RenderFailed($page_id, $message).emit()
+    };
+    (ThumbnailFailed($page_id: expr, $message: expr)) => {
+        $crate::event::Event::ThumbnailFailed($page_id, $message).emit()
+    };
 }
\ No newline at end of file
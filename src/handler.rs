@@ -4,37 +4,92 @@ use crate::emit;
 
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    app.status = None;
     match &mut app.app_state {
         AppState::Normal => {
-            deal_normal_key_event(key_event, app);
+            deal_normal_key_event(key_event, app)?;
         }
-        AppState::Search(text) => {
-            deal_search_key_event(key_event, text);
+        AppState::Search(_) => {
+            deal_search_key_event(app, key_event);
         }
         AppState::JumpPage(_) => {
             deal_jump_page_key_event(app, key_event);
         }
+        AppState::Grid(_) => {
+            deal_grid_key_event(app, key_event);
+        }
+        // any key closes the popup
+        AppState::Metadata => {
+            emit!(ChangeState(AppState::Normal));
+        }
+        AppState::LinkHint(_) => {
+            deal_link_hint_key_event(app, key_event);
+        }
+        AppState::Password(_) => {
+            deal_password_key_event(app, key_event);
+        }
     }
     Ok(())
 }
 
+fn deal_password_key_event(app: &mut App, key_event: KeyEvent) {
+    if let AppState::Password(password) = &mut app.app_state {
+        match key_event.code {
+            KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+                app.quit();
+            }
+            KeyCode::Char(c) => {
+                password.push(c);
+            }
+            KeyCode::Backspace => {
+                password.pop();
+            }
+            KeyCode::Enter => {
+                let password = password.clone();
+                app.unlock(&password);
+            }
+            // nothing to read without the password
+            KeyCode::Esc => {
+                app.quit();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn deal_link_hint_key_event(app: &mut App, key_event: KeyEvent) {
+    if let AppState::LinkHint(typed) = &mut app.app_state {
+        match key_event.code {
+            KeyCode::Char(digit) if digit.is_ascii_digit() => {
+                typed.push(digit);
+                let typed = typed.clone();
+                app.type_link_hint(&typed);
+            }
+            KeyCode::Backspace => {
+                typed.pop();
+                emit!(RenderPdf);
+            }
+            KeyCode::Esc | KeyCode::Char('f') | KeyCode::Char('q') => {
+                app.cancel_link_hints();
+            }
+            _ => {}
+        }
+    }
+}
+
 fn deal_jump_page_key_event(app: &mut App, key_event: KeyEvent) {
     if let AppState::JumpPage(page_id) = &mut app.app_state {
         match key_event.code {
-            KeyCode::Char(num) => {
-                if num.is_numeric() {
-                    page_id.push(num);
-                }
+            KeyCode::Char(c) => {
+                page_id.push(c);
             }
             KeyCode::Esc => {
                 emit!(ChangeState(AppState::Normal))
             }
             KeyCode::Enter => {
-                if let Ok(id) = page_id.parse::<u32>() {
-                    if id > 0 && id <= app.pdf_handler.get_page_nums() as u32 {
-                        app.cur_page = id;
-                        emit!(RenderPdf);
-                    }
+                match app.pdf_handler.find_page(page_id) {
+                    Some(id) => app.go_to_page(id),
+                    None => app.status = Some(format!("没有第 {page_id} 页")),
                 }
                 emit!(ChangeState(AppState::Normal))
             }
@@ -46,17 +101,60 @@ fn deal_jump_page_key_event(app: &mut App, key_event: KeyEvent) {
     }
 }
 
-fn deal_normal_key_event(key_event: KeyEvent, app: &mut App) {
+fn deal_grid_key_event(app: &mut App, key_event: KeyEvent) {
+    if let AppState::Grid(selected) = app.app_state {
+        let (columns, _, _) = app.grid_layout();
+        let page_nums = app.pdf_handler.get_page_nums() as u32;
+        let target = match key_event.code {
+            KeyCode::Left | KeyCode::Char('h') => selected.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => selected + 1,
+            KeyCode::Up | KeyCode::Char('k') => selected.saturating_sub(columns),
+            KeyCode::Down | KeyCode::Char('j') => selected + columns,
+            KeyCode::Enter => {
+                app.jump_to_grid_page(selected);
+                emit!(ChangeState(AppState::Normal));
+                return;
+            }
+            KeyCode::Esc | KeyCode::Char('g') | KeyCode::Char('q') => {
+                app.already_render = false;
+                emit!(ChangeState(AppState::Normal));
+                return;
+            }
+            _ => return,
+        };
+        if target >= 1 && target <= page_nums && target != selected {
+            app.app_state = AppState::Grid(target);
+            emit!(RenderPdf);
+        }
+    }
+}
+
+fn deal_normal_key_event(key_event: KeyEvent, app: &mut App) -> anyhow::Result<()> {
     match key_event.code {
+        // cancel the selection before quitting
+        KeyCode::Esc if app.text_selection.is_some() => {
+            app.text_selection = None;
+        }
         // Exit application on `ESC` or `q`
         KeyCode::Esc | KeyCode::Char('q') => {
             app.quit();
         }
         // Exit application on `Ctrl-C`
-        KeyCode::Char('c') | KeyCode::Char('C') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.quit();
-            }
+        KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.quit();
+        }
+        KeyCode::Char('o') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.jump_back();
+        }
+        // Ctrl-I arrives as Tab
+        KeyCode::Tab => {
+            app.jump_forward();
+        }
+        KeyCode::Backspace if key_event.modifiers == KeyModifiers::SHIFT => {
+            app.jump_forward();
+        }
+        KeyCode::Backspace => {
+            app.jump_back();
         }
         KeyCode::Char('w') => {
             app.book_marks_previous(false);
@@ -79,10 +177,28 @@ fn deal_normal_key_event(key_event: KeyEvent, app: &mut App) {
         KeyCode::Enter => {
             app.jump_to_book_mark_page();
         }
-        KeyCode::Down => {
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.scroll_down();
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.scroll_up();
+        }
+        KeyCode::Left | KeyCode::Char('h') => {
+            app.scroll_left();
+        }
+        KeyCode::Right | KeyCode::Char('l') => {
+            app.scroll_right();
+        }
+        KeyCode::PageDown => {
+            app.viewport.1 = 0;
+            app.text_scroll = 0;
+            app.text_selection = None;
             app.next_page();
         }
-        KeyCode::Up => {
+        KeyCode::PageUp => {
+            app.viewport.1 = 0;
+            app.text_scroll = 0;
+            app.text_selection = None;
             app.previous_page();
         }
         KeyCode::Char('+') => {
@@ -91,36 +207,89 @@ fn deal_normal_key_event(key_event: KeyEvent, app: &mut App) {
         KeyCode::Char('-') => {
             app.decrement_pdf_size()
         }
+        KeyCode::Char('z') => {
+            app.next_zoom_mode()
+        }
+        KeyCode::Char('c') => {
+            app.toggle_continuous()
+        }
+        KeyCode::Char('p') => {
+            app.next_spread_layout()
+        }
+        KeyCode::Char('t') => {
+            app.toggle_text_mode()
+        }
+        KeyCode::Char('f') => {
+            app.start_link_hints();
+        }
+        KeyCode::Char('i') => {
+            emit!(ChangeState(AppState::Metadata));
+        }
+        KeyCode::Char('V') => {
+            app.toggle_text_selection()
+        }
+        KeyCode::Char('y') => {
+            app.copy_text()?
+        }
+        KeyCode::Char('g') => {
+            emit!(ChangeState(AppState::Grid(app.cur_page.max(1))));
+            emit!(RenderPdf);
+        }
         KeyCode::Char('/') => {
             emit!(ChangeState(AppState::Search(String::new())));
         }
+        KeyCode::Char('n') => {
+            app.next_search_result()
+        }
+        KeyCode::Char('N') => {
+            app.previous_search_result()
+        }
         KeyCode::Char('.') => {
-            emit!(ChangeState(AppState::JumpPage(format!("{}", app.cur_page))));
+            let label = app.pdf_handler.get_page_label(app.cur_page).map(str::to_string);
+            emit!(ChangeState(AppState::JumpPage(label.unwrap_or_else(|| app.cur_page.to_string()))));
         }
-        // Counter handlers
-        KeyCode::Right => {}
-        KeyCode::Left => {}
         // Other handlers you could add here.
         _ => {}
     }
+    Ok(())
 }
 
-fn deal_search_key_event(key_event: KeyEvent, text: &mut String) {
-    match key_event.code {
-        KeyCode::Enter => {
-            // TODO search
-            emit!(ChangeState(AppState::Normal))
-        }
-        KeyCode::Char(c) => {
-            text.push(c);
-        }
-        KeyCode::Backspace => {
-            text.pop();
-        }
-        KeyCode::Esc => {
-            emit!(ChangeState(AppState::Normal))
+fn deal_search_key_event(app: &mut App, key_event: KeyEvent) {
+    if let AppState::Search(text) = &mut app.app_state {
+        match key_event.code {
+            KeyCode::Enter => {
+                let query = text.clone();
+                match app.search(&query) {
+                    Ok(_) if app.search_results.is_empty() && !query.is_empty() => {
+                        app.search_error = Some(String::from("无结果"));
+                    }
+                    Ok(_) => {
+                        app.search_error = None;
+                        emit!(ChangeState(AppState::Normal))
+                    }
+                    Err(e) => {
+                        app.search_error = Some(e.to_string());
+                    }
+                }
+            }
+            // Ctrl-R 切换正则搜索
+            KeyCode::Char('r') if key_event.modifiers == KeyModifiers::CONTROL => {
+                app.search_regex = !app.search_regex;
+            }
+            KeyCode::Char(c) => {
+                text.push(c);
+                app.search_error = None;
+            }
+            KeyCode::Backspace => {
+                text.pop();
+                app.search_error = None;
+            }
+            KeyCode::Esc => {
+                app.search_error = None;
+                emit!(ChangeState(AppState::Normal))
+            }
+            _ => {}
         }
-        _ => {}
     }
 }
 
@@ -2,21 +2,36 @@ use std::collections::HashMap;
 use std::{env, fs};
 use std::path::{PathBuf};
 use directories::BaseDirs;
+use clap::ValueEnum;
 use serde_json::{json, Value};
+use crate::error::{Error, Result};
+use crate::jump::JumpList;
+use crate::pdf::ZoomMode;
+use crate::rasterizer::RasterizerKind;
 
 /// file name
-const FILE_NAME: &'static str = "history";
+const FILE_NAME: &str = "history";
 /// Dir name
-const DIR_NAME: &'static str = "pdf-terminal-reader";
+const DIR_NAME: &str = "pdf-terminal-reader";
 
 /// {
 ///     "page": {
 ///         "pdf_path": $page_num
 ///     },
-///     "last-read": $pdf_path
+///     "zoom": {
+///         "pdf_path": $zoom_mode
+///     },
+///     "jumps": {
+///         "pdf_path": { "pages": [$page_num], "index": $index }
+///     },
+///     "last-read": $pdf_path,
+///     "rasterizer": $rasterizer
 /// }
-const PAGE: &'static str = "page";
-const LAST_READ: &'static str = "last-read";
+const PAGE: &str = "page";
+const ZOOM: &str = "zoom";
+const JUMPS: &str = "jumps";
+const LAST_READ: &str = "last-read";
+const RASTERIZER: &str = "rasterizer";
 
 pub struct History {
     /// pdf name => last read page num
@@ -25,25 +40,25 @@ pub struct History {
 }
 
 impl History {
-    pub fn init() -> Self {
-        let base_dir = BaseDirs::new().unwrap();
+    pub fn init() -> Result<Self> {
+        let base_dir = BaseDirs::new().ok_or(Error::NoDataDir)?;
         let data_dir = base_dir.data_dir();
         let pdf_history_path = data_dir.join(DIR_NAME);
         if !pdf_history_path.exists() {
-            fs::create_dir(&pdf_history_path).unwrap();
+            fs::create_dir_all(&pdf_history_path).map_err(Error::io(&pdf_history_path))?;
         }
         let pdf_history_path = pdf_history_path.join(FILE_NAME);
         let page_record = if pdf_history_path.exists() {
-            let history = fs::read_to_string(&pdf_history_path).expect("read history file failed");
-            let page_record = serde_json::from_str::<HashMap<String, Value>>(&history).expect("parse history failed");
-            Some(page_record)
+            let history = fs::read_to_string(&pdf_history_path).map_err(Error::io(&pdf_history_path))?;
+            // a damaged history is started over rather than refusing to open the pdf
+            serde_json::from_str::<HashMap<String, Value>>(&history).ok()
         } else {
             None
         };
-        Self {
+        Ok(Self {
             page_record,
             file_path: pdf_history_path,
-        }
+        })
     }
     pub fn read_last_page_num(&self, pdf_path: &str) -> Option<u32> {
         if let Some(history) = self.page_record.as_ref() {
@@ -63,27 +78,75 @@ impl History {
         None
     }
 
-    pub fn save_history(&mut self, pdf_path: &str, page_num: u32) {
+    /// Saves the page and everything set with the `set_*` methods.
+    pub fn save_history(&mut self, pdf_path: &str, page_num: u32) -> Result<()> {
+        self.set_record(PAGE, pdf_path, json!(page_num));
+        let file_name = Self::absolute_path(pdf_path);
+        let history = self.page_record.get_or_insert_with(HashMap::new);
+        history.insert(String::from(LAST_READ), json!(&file_name));
+        // a map of json values always serializes
+        let data = serde_json::to_vec(history).unwrap_or_default();
+        fs::write(&self.file_path, data).map_err(Error::io(&self.file_path))
+    }
+
+    pub fn read_zoom(&self, pdf_path: &str) -> Option<ZoomMode> {
+        match self.read_record(ZOOM, pdf_path) {
+            Some(Value::String(zoom)) => zoom.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Remembers the zoom mode, written by [`History::save_history`].
+    pub fn set_zoom(&mut self, pdf_path: &str, zoom: ZoomMode) {
+        self.set_record(ZOOM, pdf_path, json!(zoom.to_string()));
+    }
+
+    pub fn read_jumps(&self, pdf_path: &str) -> Option<JumpList> {
+        serde_json::from_value(self.read_record(JUMPS, pdf_path)?.clone()).ok()
+    }
+
+    /// Remembers the jump list, written by [`History::save_history`].
+    pub fn set_jumps(&mut self, pdf_path: &str, jumps: &JumpList) {
+        if let Ok(jumps) = serde_json::to_value(jumps) {
+            self.set_record(JUMPS, pdf_path, jumps);
+        }
+    }
+
+    /// The rasterizer last picked with `--rasterizer`, for every pdf.
+    pub fn read_rasterizer(&self) -> Option<RasterizerKind> {
+        match self.page_record.as_ref()?.get(RASTERIZER) {
+            Some(Value::String(kind)) => RasterizerKind::from_str(kind, true).ok(),
+            _ => None,
+        }
+    }
+
+    /// Remembers the rasterizer as the default, written by [`History::save_history`].
+    pub fn set_rasterizer(&mut self, kind: RasterizerKind) {
+        if let Some(kind) = kind.to_possible_value() {
+            let history = self.page_record.get_or_insert_with(HashMap::new);
+            history.insert(String::from(RASTERIZER), json!(kind.get_name()));
+        }
+    }
+
+    fn absolute_path(pdf_path: &str) -> String {
         let mut file_name = PathBuf::from(pdf_path);
         if file_name.is_relative() {
             file_name = env::current_dir().unwrap().join(file_name);
         }
-        let file_name = file_name.display().to_string();
-        if let Some(history) = self.page_record.as_mut() {
-            history.insert(String::from(LAST_READ), json!(&file_name));
-            if let Some(Value::Object(page_history)) = history.get_mut(PAGE) {
-                page_history.insert(file_name, json!(page_num));
-                let data = serde_json::to_vec(history).unwrap();
-                fs::write(&self.file_path, data).unwrap();
-            }
-        } else {
-            let mut history: HashMap<String, Value> = HashMap::new();
-            history.insert(String::from(LAST_READ), json!(&file_name));
-            let mut page_history: HashMap<String, Value> = HashMap::new();
-            page_history.insert(file_name, json!(page_num));
-            history.insert(PAGE.into(), json!(page_history));
-            let data = serde_json::to_vec(&history).unwrap();
-            fs::write(&self.file_path, data).unwrap();
+        file_name.display().to_string()
+    }
+
+    /// `history[key][pdf_path]`
+    fn read_record(&self, key: &str, pdf_path: &str) -> Option<&Value> {
+        self.page_record.as_ref()?.get(key)?.get(Self::absolute_path(pdf_path))
+    }
+
+    fn set_record(&mut self, key: &str, pdf_path: &str, value: Value) {
+        let file_name = Self::absolute_path(pdf_path);
+        let history = self.page_record.get_or_insert_with(HashMap::new);
+        let records = history.entry(key.to_string()).or_insert_with(|| json!({}));
+        if let Value::Object(records) = records {
+            records.insert(file_name, value);
         }
     }
 
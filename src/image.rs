@@ -1,16 +1,93 @@
 use std::io::{stdout, Write};
-use base64::Engine;
-use base64::engine::general_purpose;
+use ::image::DynamicImage;
+use clap::ValueEnum;
 use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
 use crossterm::queue;
 use ratatui::prelude::Rect;
 use tokio::sync::{Mutex};
 use crate::pdf::PdfSize;
 
+/// iTerm2 inline image protocol
+mod iterm2;
+
+/// kitty graphics protocol
+mod kitty;
+
+/// DEC sixel graphics
+mod sixel;
+
+/// unicode half block / braille fallback
+mod unicode;
+
+/// terminal graphics capability detection
+mod detect;
+
+/// tmux / screen passthrough
+mod passthrough;
+
+pub use detect::{cell_size_from_ioctl, detect, TerminalGraphics};
+pub use passthrough::Multiplexer;
+pub use unicode::UnicodeImage;
+
+/// Terminal graphics protocol used to draw the pdf page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum GraphicsProtocol {
+    /// `OSC 1337;File=` (iTerm2, WezTerm)
+    #[default]
+    Iterm2,
+    /// `APC G` (kitty, Ghostty, WezTerm)
+    Kitty,
+    /// DCS sixel (foot, xterm, mlterm, Windows Terminal)
+    Sixel,
+    /// `▀` half blocks in truecolor, no image protocol needed
+    Halfblocks,
+    /// monochrome braille dots, no image protocol needed
+    Braille,
+}
+
+/// A page ready to draw.
+#[derive(Debug, Clone)]
+pub struct PageImage {
+    pub image: DynamicImage,
+    /// the cached jpeg `image` was decoded from, while nothing has been drawn on it
+    pub jpeg: Option<Vec<u8>>,
+}
+
+impl PageImage {
+    pub fn from_jpeg(jpeg: Vec<u8>) -> anyhow::Result<Self> {
+        let image = ::image::load_from_memory(&jpeg)?;
+        Ok(Self { image, jpeg: Some(jpeg) })
+    }
+}
+
+impl From<DynamicImage> for PageImage {
+    fn from(image: DynamicImage) -> Self {
+        Self { image, jpeg: None }
+    }
+}
+
+/// Used when the terminal doesn't report its cell size
+const DEFAULT_CELL_SIZE: (u16, u16) = (10, 20);
+
 #[derive(Debug)]
 pub struct ImageHandler {
     image_area: Option<Rect>,
     pdf_lock: Mutex<()>,
+    protocol: GraphicsProtocol,
+    /// kitty image id of the page currently on screen
+    kitty_image_id: Option<u32>,
+    /// page drawn by the unicode renderer on the next frame
+    page_image: Option<DynamicImage>,
+    /// tmux / screen the escapes have to pass through
+    multiplexer: Option<Multiplexer>,
+    /// (width, height) of a terminal cell in pixels
+    cell_size: (u16, u16),
+}
+
+impl Default for ImageHandler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ImageHandler {
@@ -18,6 +95,11 @@ impl ImageHandler {
         Self {
             image_area: None,
             pdf_lock: Mutex::new(()),
+            protocol: GraphicsProtocol::default(),
+            kitty_image_id: None,
+            page_image: None,
+            multiplexer: Multiplexer::detect(),
+            cell_size: DEFAULT_CELL_SIZE,
         }
     }
 
@@ -25,16 +107,90 @@ impl ImageHandler {
         self.image_area = Some(image_area);
     }
 
+    pub fn get_protocol(&self) -> GraphicsProtocol {
+        self.protocol
+    }
+
+    pub fn set_protocol(&mut self, protocol: GraphicsProtocol) {
+        self.protocol = protocol;
+    }
+
+    pub fn get_multiplexer(&self) -> Option<Multiplexer> {
+        self.multiplexer
+    }
+
+    pub fn get_cell_size(&self) -> (u16, u16) {
+        self.cell_size
+    }
+
+    pub fn set_cell_size(&mut self, cell_size: Option<(u16, u16)>) {
+        self.cell_size = cell_size.unwrap_or(DEFAULT_CELL_SIZE);
+    }
+
+    /// Re-read what may have changed after a resize: the pane offset and,
+    /// if the font size changed, the cell size.
+    pub fn refresh_after_resize(&mut self) {
+        self.multiplexer = Multiplexer::detect();
+        if let Some(cell_size) = cell_size_from_ioctl() {
+            self.cell_size = cell_size;
+        }
+    }
+
+    /// Unicode renderers draw as a widget inside ratatui's frame instead of writing escapes.
+    pub fn is_unicode(&self) -> bool {
+        matches!(self.protocol, GraphicsProtocol::Halfblocks | GraphicsProtocol::Braille)
+    }
 
-    pub fn render_image(&self, image_data: &[u8], pdf_size: &PdfSize) -> anyhow::Result<()> {
-        let b64 = general_purpose::STANDARD.encode(image_data);
-        let mut buf = vec![];
-        write!(buf, "\x1b]1337;File=inline=1;size={};width={}px;height={}px;doNotMoveCursor=1:{}\x07",
-               image_data.len(),
-               pdf_size.width(),
-               pdf_size.height(),
-               b64
-        )?;
+    /// Widget for the unicode renderers, once a page has been rendered.
+    pub fn unicode_widget(&self) -> Option<UnicodeImage<'_>> {
+        let image = self.page_image.as_ref()?;
+        match self.protocol {
+            GraphicsProtocol::Halfblocks => Some(UnicodeImage::half_blocks(image)),
+            GraphicsProtocol::Braille => Some(UnicodeImage::braille(image)),
+            _ => None,
+        }
+    }
+
+    /// Forget the page on screen, kitty keeps its images until told to delete them.
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        self.page_image = None;
+        if let Some(image_id) = self.kitty_image_id.take() {
+            let buf = kitty::delete(image_id);
+            let buf = match self.multiplexer {
+                Some(multiplexer) => multiplexer.passthrough(&buf),
+                None => buf,
+            };
+            let mut stdout = stdout().lock();
+            stdout.write_all(&buf)?;
+            stdout.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn render_image(&mut self, page: &PageImage, pdf_size: &PdfSize) -> anyhow::Result<()> {
+        let image = &page.image;
+        if self.is_unicode() {
+            self.page_image = Some(image.clone());
+            return Ok(());
+        }
+        let buf = match self.protocol {
+            GraphicsProtocol::Iterm2 => iterm2::encode(page)?,
+            GraphicsProtocol::Sixel => sixel::encode(image)?,
+            GraphicsProtocol::Halfblocks | GraphicsProtocol::Braille => unreachable!(),
+            GraphicsProtocol::Kitty => {
+                let image_id = kitty::next_image_id(self.kitty_image_id);
+                let mut buf = kitty::transmit(image, image_id)?;
+                // remove the previous page once the new one is placed over it
+                if let Some(old_id) = self.kitty_image_id.replace(image_id) {
+                    buf.extend(kitty::delete(old_id));
+                }
+                buf
+            }
+        };
+        let buf = match self.multiplexer {
+            Some(multiplexer) => multiplexer.wrap(&buf, (pdf_size.x(), pdf_size.y())),
+            None => buf,
+        };
         let _lock = self.pdf_lock.lock();
         move_lock(stdout().lock(), (pdf_size.x(), pdf_size.y()), |stdout| {
             stdout.write_all(&buf)?;
@@ -44,7 +200,6 @@ impl ImageHandler {
     }
 }
 
-
 #[inline]
 pub fn move_lock<W, F, T>(mut stdout: W, (x, y): (u16, u16), cb: F) -> anyhow::Result<T>
     where
@@ -59,4 +214,4 @@ pub fn move_lock<W, F, T>(mut stdout: W, (x, y): (u16, u16), cb: F) -> anyhow::R
         stdout.flush()?;
         result
     }
-}
\ No newline at end of file
+}
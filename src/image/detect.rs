@@ -0,0 +1,165 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::fd::AsRawFd;
+use std::time::{Duration, Instant};
+use super::{GraphicsProtocol, Multiplexer};
+
+/// kitty graphics query: a 1x1 RGB image that is checked but never stored
+const KITTY_QUERY: &str = "\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\";
+/// Cell size in pixels, `CSI 6 ; height ; width t`
+const CELL_SIZE_QUERY: &str = "\x1b[16t";
+/// Primary device attributes, answered by every terminal
+const DA1_QUERY: &str = "\x1b[c";
+
+/// Graphics capabilities probed from the terminal.
+#[derive(Debug, Clone, Copy)]
+pub struct TerminalGraphics {
+    pub protocol: GraphicsProtocol,
+    /// (width, height) of a cell in pixels
+    pub cell_size: Option<(u16, u16)>,
+}
+
+/// Pick the best graphics protocol the terminal supports, unless `protocol`
+/// is forced, and find out how many pixels a cell has.
+///
+/// Must be called in raw mode and before anything else reads from the tty,
+/// the answers to the queries arrive on stdin.
+pub fn detect(protocol: Option<GraphicsProtocol>, timeout: Duration) -> TerminalGraphics {
+    let protocol = protocol.or_else(from_env);
+    let cell_size = cell_size_from_ioctl();
+    if let (Some(protocol), Some(_)) = (protocol, cell_size) {
+        return TerminalGraphics { protocol, cell_size };
+    }
+    let response = query(timeout).unwrap_or_default();
+    let protocol = protocol.unwrap_or_else(|| {
+        if response.windows(b"i=31;OK".len()).any(|w| w == b"i=31;OK") {
+            GraphicsProtocol::Kitty
+        } else if supports_sixel(&response) {
+            GraphicsProtocol::Sixel
+        } else {
+            fallback()
+        }
+    });
+    TerminalGraphics { protocol, cell_size: cell_size.or_else(|| parse_cell_size(&response)) }
+}
+
+/// Cell size from `TIOCGWINSZ`, many terminals leave the pixel fields at 0.
+pub fn cell_size_from_ioctl() -> Option<(u16, u16)> {
+    let size = crossterm::terminal::window_size().ok()?;
+    if size.width == 0 || size.height == 0 || size.columns == 0 || size.rows == 0 {
+        return None;
+    }
+    Some((size.width / size.columns, size.height / size.rows))
+}
+
+/// Terminals that announce themselves through the environment.
+fn from_env() -> Option<GraphicsProtocol> {
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+    let lc_terminal = env::var("LC_TERMINAL").unwrap_or_default();
+    if term_program == "iTerm.app" || term_program == "WezTerm" || lc_terminal == "iTerm2" {
+        return Some(GraphicsProtocol::Iterm2);
+    }
+    let term = env::var("TERM").unwrap_or_default();
+    if env::var_os("KITTY_WINDOW_ID").is_some() || term.contains("kitty") || term.contains("ghostty") {
+        return Some(GraphicsProtocol::Kitty);
+    }
+    None
+}
+
+/// No image protocol: truecolor half blocks if possible, braille otherwise.
+fn fallback() -> GraphicsProtocol {
+    match env::var("COLORTERM").as_deref() {
+        Ok("truecolor") | Ok("24bit") => GraphicsProtocol::Halfblocks,
+        _ => GraphicsProtocol::Braille,
+    }
+}
+
+/// Send the queries and collect the answers up to the DA1 response.
+fn query(timeout: Duration) -> anyhow::Result<Vec<u8>> {
+    let mut tty = File::options().read(true).write(true).open("/dev/tty")?;
+    // the multiplexer answers DA1 itself, only the kitty query reaches the real terminal
+    let kitty_query = match Multiplexer::detect() {
+        Some(multiplexer) => multiplexer.wrap(KITTY_QUERY.as_bytes(), (0, 0)),
+        None => KITTY_QUERY.as_bytes().to_vec(),
+    };
+    tty.write_all(&kitty_query)?;
+    write!(tty, "{CELL_SIZE_QUERY}{DA1_QUERY}")?;
+    tty.flush()?;
+
+    // read here instead of in a thread: a reader left blocked on the tty
+    // would eat the first key typed once the ui is up
+    let deadline = Instant::now() + timeout;
+    let mut response = vec![];
+    let mut buf = [0; 256];
+    while !has_da1_response(&response) {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() || !wait_readable(&tty, left)? {
+            break;
+        }
+        match tty.read(&mut buf)? {
+            0 => break,
+            n => response.extend_from_slice(&buf[..n]),
+        }
+    }
+    Ok(response)
+}
+
+/// Wait up to `timeout` for the tty to have something to read.
+fn wait_readable(tty: &File, timeout: Duration) -> io::Result<bool> {
+    let mut fd = libc::pollfd { fd: tty.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+    let timeout = timeout.as_millis().clamp(1, libc::c_int::MAX as u128) as libc::c_int;
+    loop {
+        match unsafe { libc::poll(&mut fd, 1, timeout) } {
+            -1 if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => continue,
+            -1 => return Err(io::Error::last_os_error()),
+            n => return Ok(n > 0),
+        }
+    }
+}
+
+/// Find `CSI ? Ps ; ... c` in the collected bytes.
+fn da1_params(response: &[u8]) -> Option<&[u8]> {
+    let start = response.windows(3).position(|w| w == b"\x1b[?")? + 3;
+    let len = response[start..].iter().position(|b| *b == b'c')?;
+    Some(&response[start..start + len])
+}
+
+fn has_da1_response(response: &[u8]) -> bool {
+    da1_params(response).is_some()
+}
+
+/// `CSI 6 ; height ; width t`
+fn parse_cell_size(response: &[u8]) -> Option<(u16, u16)> {
+    let start = response.windows(4).position(|w| w == b"\x1b[6;")? + 4;
+    let len = response[start..].iter().position(|b| *b == b't')?;
+    let params = std::str::from_utf8(&response[start..start + len]).ok()?;
+    let (height, width) = params.split_once(';')?;
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Some((width, height)),
+        _ => None,
+    }
+}
+
+/// Attribute `4` in the DA1 answer means sixel graphics.
+fn supports_sixel(response: &[u8]) -> bool {
+    da1_params(response).is_some_and(|params| params.split(|b| *b == b';').any(|p| p == b"4"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_cell_size, supports_sixel};
+
+    #[test]
+    fn sixel_attribute() {
+        assert!(supports_sixel(b"\x1b_Gi=31;ENOTSUPPORTED\x1b\\\x1b[?62;4;6;22c"));
+        assert!(!supports_sixel(b"\x1b[?62;22;42c"));
+        assert!(!supports_sixel(b""));
+    }
+
+    #[test]
+    fn cell_size() {
+        assert_eq!(parse_cell_size(b"\x1b[6;20;9t\x1b[?62;22c"), Some((9, 20)));
+        assert_eq!(parse_cell_size(b"\x1b[?62;22c"), None);
+    }
+}
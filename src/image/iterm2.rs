@@ -0,0 +1,48 @@
+use std::io::{Cursor, Write};
+use base64::Engine;
+use base64::engine::general_purpose;
+use image::codecs::jpeg::JpegEncoder;
+use super::PageImage;
+
+/// Encode the page as an iTerm2 `OSC 1337;File=` inline image,
+/// shown at its own pixel size.
+///
+/// An untouched page is sent as the jpeg it is cached as, only
+/// composed or drawn on pages are encoded again.
+pub fn encode(page: &PageImage) -> anyhow::Result<Vec<u8>> {
+    let encoded;
+    let image_data = match &page.jpeg {
+        Some(jpeg) => jpeg,
+        None => {
+            let mut jpg = Cursor::new(vec![]);
+            JpegEncoder::new_with_quality(&mut jpg, 90).encode_image(&page.image)?;
+            encoded = jpg.into_inner();
+            &encoded
+        }
+    };
+    let b64 = general_purpose::STANDARD.encode(image_data);
+    let mut buf = vec![];
+    write!(buf, "\x1b]1337;File=inline=1;size={};width={}px;height={}px;doNotMoveCursor=1:{}\x07",
+           image_data.len(),
+           page.image.width(),
+           page.image.height(),
+           b64
+    )?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+    use base64::engine::general_purpose;
+    use image::DynamicImage;
+    use super::{encode, PageImage};
+
+    #[test]
+    fn cached_jpeg_sent_as_is() {
+        let page = PageImage { image: DynamicImage::new_rgb8(3, 2), jpeg: Some(b"not reencoded".to_vec()) };
+        let buf = String::from_utf8(encode(&page).unwrap()).unwrap();
+        let b64 = general_purpose::STANDARD.encode(b"not reencoded");
+        assert_eq!(buf, format!("\x1b]1337;File=inline=1;size=13;width=3px;height=2px;doNotMoveCursor=1:{b64}\x07"));
+    }
+}
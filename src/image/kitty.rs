@@ -0,0 +1,91 @@
+use std::io::{Cursor, Write};
+use base64::Engine;
+use base64::engine::general_purpose;
+use image::{DynamicImage, ImageOutputFormat};
+
+/// kitty accepts at most 4096 bytes of base64 payload per escape
+const CHUNK_SIZE: usize = 4096;
+
+/// Two ids are enough: the page on screen and the one replacing it.
+pub fn next_image_id(current: Option<u32>) -> u32 {
+    match current {
+        Some(1) => 2,
+        _ => 1,
+    }
+}
+
+/// Transmit the page as PNG and place it at the cursor.
+///
+/// `q=2` suppresses the terminal's responses and `C=1` keeps the cursor
+/// where it is so ratatui's bookkeeping stays valid.
+pub fn transmit(image: &DynamicImage, image_id: u32) -> anyhow::Result<Vec<u8>> {
+    let mut png = Cursor::new(vec![]);
+    image.write_to(&mut png, ImageOutputFormat::Png)?;
+    let b64 = general_purpose::STANDARD.encode(png.into_inner());
+    let chunks: Vec<&[u8]> = b64.as_bytes().chunks(CHUNK_SIZE).collect();
+    let mut buf = vec![];
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            write!(buf, "\x1b_Ga=T,f=100,i={image_id},q=2,C=1,m={more};")?;
+        } else {
+            write!(buf, "\x1b_Gm={more};")?;
+        }
+        buf.extend_from_slice(chunk);
+        buf.extend_from_slice(b"\x1b\\");
+    }
+    Ok(buf)
+}
+
+/// Delete every placement of the image and free its data.
+pub fn delete(image_id: u32) -> Vec<u8> {
+    format!("\x1b_Ga=d,d=I,i={image_id},q=2\x1b\\").into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, Rgb, RgbImage};
+    use super::{delete, next_image_id, transmit, CHUNK_SIZE};
+
+    #[test]
+    fn chunked_transmit() {
+        // noise so the png stays larger than a few chunks
+        let noise = RgbImage::from_fn(64, 64, |x, y| {
+            let v = (x * 7919 + y * 104729).wrapping_mul(2654435761);
+            Rgb([v as u8, (v >> 8) as u8, (v >> 16) as u8])
+        });
+        let buf = String::from_utf8(transmit(&DynamicImage::ImageRgb8(noise), 2).unwrap()).unwrap();
+        let escapes: Vec<&str> = buf.split_terminator("\x1b\\").collect();
+        assert!(escapes.len() > 2);
+        let last = escapes.len() - 1;
+        for (i, escape) in escapes.iter().enumerate() {
+            let (control, payload) = escape.split_once(';').unwrap();
+            match i {
+                0 => assert_eq!(control, "\x1b_Ga=T,f=100,i=2,q=2,C=1,m=1"),
+                _ if i == last => assert_eq!(control, "\x1b_Gm=0"),
+                _ => assert_eq!(control, "\x1b_Gm=1"),
+            }
+            if i == last {
+                assert!(payload.len() <= CHUNK_SIZE);
+            } else {
+                assert_eq!(payload.len(), CHUNK_SIZE);
+            }
+        }
+    }
+
+    #[test]
+    fn single_chunk() {
+        let buf = transmit(&DynamicImage::new_rgb8(1, 1), 1).unwrap();
+        let buf = String::from_utf8(buf).unwrap();
+        assert!(buf.starts_with("\x1b_Ga=T,f=100,i=1,q=2,C=1,m=0;"));
+        assert_eq!(buf.matches("\x1b\\").count(), 1);
+    }
+
+    #[test]
+    fn delete_and_ids() {
+        assert_eq!(delete(2), b"\x1b_Ga=d,d=I,i=2,q=2\x1b\\");
+        assert_eq!(next_image_id(None), 1);
+        assert_eq!(next_image_id(Some(1)), 2);
+        assert_eq!(next_image_id(Some(2)), 1);
+    }
+}
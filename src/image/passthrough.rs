@@ -0,0 +1,134 @@
+use std::env;
+use std::process::Command;
+
+/// GNU screen truncates DCS strings longer than this
+const SCREEN_CHUNK_SIZE: usize = 768;
+
+/// Terminal multiplexer between the reader and the real terminal.
+///
+/// Multiplexers swallow graphics escapes unless they are wrapped in a
+/// DCS passthrough, and they don't translate the cursor position of the
+/// wrapped sequence, so the image has to be positioned in the outer
+/// terminal's coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplexer {
+    /// tmux, `set -g allow-passthrough on` is required since tmux 3.3
+    Tmux {
+        /// pane offset inside the tmux window
+        left: u16,
+        top: u16,
+    },
+    /// GNU screen
+    Screen,
+}
+
+impl Multiplexer {
+    /// Detect the multiplexer from `$TMUX` / `$STY`.
+    pub fn detect() -> Option<Self> {
+        if env::var_os("TMUX").is_some() {
+            let (left, top) = tmux_pane_offset().unwrap_or((0, 0));
+            Some(Multiplexer::Tmux { left, top })
+        } else if env::var_os("STY").is_some() {
+            Some(Multiplexer::Screen)
+        } else {
+            None
+        }
+    }
+
+    /// Wrap escape sequences that don't draw anything, so nothing has to be positioned.
+    pub fn passthrough(&self, buf: &[u8]) -> Vec<u8> {
+        match self {
+            Multiplexer::Tmux { .. } => split_sequences(buf).into_iter().flat_map(tmux_passthrough).collect(),
+            Multiplexer::Screen => self.wrap(buf, (0, 0)),
+        }
+    }
+
+    /// Wrap every escape sequence in `buf` for the multiplexer.
+    ///
+    /// `(x, y)` is the cell inside the pane the image belongs at.
+    pub fn wrap(&self, buf: &[u8], (x, y): (u16, u16)) -> Vec<u8> {
+        let mut out = vec![];
+        match self {
+            Multiplexer::Tmux { left, top } => {
+                // save the outer cursor and move it where the pane cell really is
+                let position = format!("\x1b7\x1b[{};{}H", top + y + 1, left + x + 1);
+                out.extend(tmux_passthrough(position.as_bytes()));
+                for seq in split_sequences(buf) {
+                    out.extend(tmux_passthrough(seq));
+                }
+                out.extend(tmux_passthrough(b"\x1b8"));
+            }
+            Multiplexer::Screen => {
+                for seq in split_sequences(buf) {
+                    for chunk in seq.chunks(SCREEN_CHUNK_SIZE) {
+                        out.extend_from_slice(b"\x1bP");
+                        out.extend_from_slice(chunk);
+                        out.extend_from_slice(b"\x1b\\");
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// `DCS tmux; <seq with ESC doubled> ST`
+fn tmux_passthrough(seq: &[u8]) -> Vec<u8> {
+    let mut out = b"\x1bPtmux;".to_vec();
+    for b in seq {
+        if *b == 0x1b {
+            out.push(0x1b);
+        }
+        out.push(*b);
+    }
+    out.extend_from_slice(b"\x1b\\");
+    out
+}
+
+/// Split the buffer before every ESC that starts a new sequence,
+/// the `ESC \` string terminators stay with their sequence.
+fn split_sequences(buf: &[u8]) -> Vec<&[u8]> {
+    let mut sequences = vec![];
+    let mut start = 0;
+    for i in 1..buf.len() {
+        if buf[i] == 0x1b && buf.get(i + 1) != Some(&b'\\') {
+            sequences.push(&buf[start..i]);
+            start = i;
+        }
+    }
+    if start < buf.len() {
+        sequences.push(&buf[start..]);
+    }
+    sequences
+}
+
+fn tmux_pane_offset() -> Option<(u16, u16)> {
+    let output = Command::new("tmux")
+        .args(["display-message", "-p", "#{pane_left} #{pane_top}"])
+        .output()
+        .ok()?;
+    let output = String::from_utf8(output.stdout).ok()?;
+    let mut offset = output.split_whitespace().map(|n| n.parse::<u16>());
+    match (offset.next(), offset.next()) {
+        (Some(Ok(left)), Some(Ok(top))) => Some((left, top)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Multiplexer;
+
+    #[test]
+    fn tmux_wrap() {
+        let tmux = Multiplexer::Tmux { left: 10, top: 2 };
+        let wrapped = tmux.wrap(b"\x1b_Gm=1;AA\x1b\\\x1b_Gm=0;BB\x1b\\", (1, 1));
+        assert_eq!(
+            String::from_utf8(wrapped).unwrap(),
+            "\x1bPtmux;\x1b\x1b7\x1b\x1b[4;12H\x1b\\\
+             \x1bPtmux;\x1b\x1b_Gm=1;AA\x1b\x1b\\\x1b\\\
+             \x1bPtmux;\x1b\x1b_Gm=0;BB\x1b\x1b\\\x1b\\\
+             \x1bPtmux;\x1b\x1b8\x1b\\"
+        );
+    }
+}
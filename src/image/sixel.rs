@@ -0,0 +1,123 @@
+use std::io::Write;
+use image::{DynamicImage, Rgb, RgbImage};
+
+/// levels per channel of the fixed color cube
+const LEVELS: u32 = 6;
+
+/// Encode the page as a DEC sixel image.
+///
+/// Colors are quantized to a fixed 6x6x6 cube, which is plenty for
+/// rendered documents and keeps the output deterministic.
+pub fn encode(image: &DynamicImage) -> anyhow::Result<Vec<u8>> {
+    encode_rgb(&image.to_rgb8())
+}
+
+fn encode_rgb(image: &RgbImage) -> anyhow::Result<Vec<u8>> {
+    let (width, height) = image.dimensions();
+    let indexes: Vec<u8> = image.pixels().map(palette_index).collect();
+    let mut used = [false; (LEVELS * LEVELS * LEVELS) as usize];
+    for index in indexes.iter() {
+        used[*index as usize] = true;
+    }
+
+    let mut buf = vec![];
+    // P2=1 => pixels that are not drawn keep the background
+    write!(buf, "\x1bP0;1;0q\"1;1;{width};{height}")?;
+    for (index, _) in used.iter().enumerate().filter(|(_, used)| **used) {
+        let [r, g, b] = palette_color(index as u8);
+        write!(buf, "#{index};2;{};{};{}", percent(r), percent(g), percent(b))?;
+    }
+
+    // sixels of each color in the current band, bucketed in one pass over its pixels
+    let mut bands: Vec<Vec<u8>> = vec![vec![]; used.len()];
+    for band in (0..height).step_by(6) {
+        let rows = (height - band).min(6);
+        for row in 0..rows {
+            let start = ((band + row) * width) as usize;
+            for (x, color) in indexes[start..start + width as usize].iter().enumerate() {
+                let sixels = &mut bands[*color as usize];
+                if sixels.is_empty() {
+                    sixels.resize(width as usize, 0);
+                }
+                sixels[x] |= 1 << row;
+            }
+        }
+        let mut first = true;
+        for (color, sixels) in bands.iter_mut().enumerate().filter(|(_, sixels)| !sixels.is_empty()) {
+            // empty sixels at the end of a line need not be drawn
+            let len = sixels.iter().rposition(|bits| *bits != 0).map_or(0, |i| i + 1);
+            if !first {
+                buf.push(b'$');
+            }
+            first = false;
+            write!(buf, "#{color}")?;
+            write_run_length(&mut buf, &sixels[..len])?;
+            sixels.clear();
+        }
+        buf.push(b'-');
+    }
+    buf.extend_from_slice(b"\x1b\\");
+    Ok(buf)
+}
+
+/// Write the sixel characters of one color, collapsing repeats with `!n`.
+fn write_run_length(buf: &mut Vec<u8>, sixels: &[u8]) -> anyhow::Result<()> {
+    let mut i = 0;
+    while i < sixels.len() {
+        let bits = sixels[i];
+        let mut run = 1;
+        while i + run < sixels.len() && sixels[i + run] == bits {
+            run += 1;
+        }
+        let ch = (bits + 0x3f) as char;
+        if run > 3 {
+            write!(buf, "!{run}{ch}")?;
+        } else {
+            for _ in 0..run {
+                write!(buf, "{ch}")?;
+            }
+        }
+        i += run;
+    }
+    Ok(())
+}
+
+fn palette_index(pixel: &Rgb<u8>) -> u8 {
+    let level = |c: u8| (c as u32 * (LEVELS - 1) + 127) / 255;
+    let [r, g, b] = pixel.0;
+    (level(r) * LEVELS * LEVELS + level(g) * LEVELS + level(b)) as u8
+}
+
+fn palette_color(index: u8) -> [u8; 3] {
+    let index = index as u32;
+    let value = |level: u32| (level * 255 / (LEVELS - 1)) as u8;
+    [value(index / (LEVELS * LEVELS)), value(index / LEVELS % LEVELS), value(index % LEVELS)]
+}
+
+fn percent(c: u8) -> u32 {
+    (c as u32 * 100 + 127) / 255
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgb, RgbImage};
+    use super::encode_rgb;
+
+    #[test]
+    fn encode_small_image() {
+        // 4x7: white background, black first column, red bottom row
+        let mut image = RgbImage::from_pixel(4, 7, Rgb([255, 255, 255]));
+        for y in 0..7 {
+            image.put_pixel(0, y, Rgb([0, 0, 0]));
+        }
+        for x in 1..4 {
+            image.put_pixel(x, 6, Rgb([250, 10, 5]));
+        }
+        let data = encode_rgb(&image).unwrap();
+        assert_eq!(
+            String::from_utf8(data).unwrap(),
+            "\x1bP0;1;0q\"1;1;4;7#0;2;0;0;0#180;2;100;0;0#215;2;100;100;100\
+             #0~$#215?~~~-#0@$#180?@@@-\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use image::{DynamicImage, GenericImageView};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::Widget;
+
+/// Draws the page with unicode characters, for terminals without an image protocol.
+pub struct UnicodeImage<'a> {
+    image: &'a DynamicImage,
+    braille: bool,
+}
+
+/// Bit of each dot in a braille cell, indexed by `[y][x]`.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+impl<'a> UnicodeImage<'a> {
+    /// `▀` half blocks, two truecolor pixels per cell.
+    pub fn half_blocks(image: &'a DynamicImage) -> Self {
+        Self { image, braille: false }
+    }
+
+    /// Braille dots, 2x4 monochrome pixels per cell.
+    pub fn braille(image: &'a DynamicImage) -> Self {
+        Self { image, braille: true }
+    }
+
+    fn render_half_blocks(&self, area: Rect, buf: &mut Buffer) {
+        let image = self.image.thumbnail(area.width as u32, area.height as u32 * 2).to_rgb8();
+        let offset_x = area.x + (area.width - image.width() as u16) / 2;
+        for y in 0..(image.height() as u16).div_ceil(2) {
+            for x in 0..image.width() as u16 {
+                let top = image.get_pixel(x as u32, y as u32 * 2).0;
+                let cell = buf.get_mut(offset_x + x, area.y + y);
+                cell.set_char('▀').set_fg(Color::Rgb(top[0], top[1], top[2]));
+                if let Some(bottom) = image.get_pixel_checked(x as u32, y as u32 * 2 + 1) {
+                    cell.set_bg(Color::Rgb(bottom[0], bottom[1], bottom[2]));
+                }
+            }
+        }
+    }
+
+    fn render_braille(&self, area: Rect, buf: &mut Buffer) {
+        let image = self.image.thumbnail(area.width as u32 * 2, area.height as u32 * 4).to_luma8();
+        let (width, height) = image.dimensions();
+        let offset_x = area.x + (area.width - (width as u16).div_ceil(2)) / 2;
+        for y in 0..height.div_ceil(4) {
+            for x in 0..width.div_ceil(2) {
+                let mut dots = 0;
+                for (dy, row) in BRAILLE_DOTS.iter().enumerate() {
+                    for (dx, bit) in row.iter().enumerate() {
+                        let (px, py) = (x * 2 + dx as u32, y * 4 + dy as u32);
+                        if image.in_bounds(px, py) && image.get_pixel(px, py).0[0] < 128 {
+                            dots |= bit;
+                        }
+                    }
+                }
+                let ch = char::from_u32(0x2800 + dots).unwrap_or(' ');
+                buf.get_mut(offset_x + x as u16, area.y + y as u16).set_char(ch);
+            }
+        }
+    }
+}
+
+impl Widget for UnicodeImage<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        if self.braille {
+            self.render_braille(area, buf);
+        } else {
+            self.render_half_blocks(area, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, Rgb, RgbImage};
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::style::Color;
+    use ratatui::widgets::Widget;
+    use super::UnicodeImage;
+
+    fn two_tone(top: Rgb<u8>) -> DynamicImage {
+        // bottom half black
+        let mut image = RgbImage::from_pixel(2, 4, top);
+        for x in 0..2 {
+            for y in 2..4 {
+                image.put_pixel(x, y, Rgb([0, 0, 0]));
+            }
+        }
+        DynamicImage::ImageRgb8(image)
+    }
+
+    #[test]
+    fn half_blocks() {
+        let area = Rect::new(0, 0, 2, 2);
+        let mut buf = Buffer::empty(area);
+        UnicodeImage::half_blocks(&two_tone(Rgb([255, 0, 0]))).render(area, &mut buf);
+        let cell = buf.get(0, 0);
+        assert_eq!(cell.symbol(), "▀");
+        assert_eq!((cell.fg, cell.bg), (Color::Rgb(255, 0, 0), Color::Rgb(255, 0, 0)));
+        let cell = buf.get(1, 1);
+        assert_eq!((cell.fg, cell.bg), (Color::Rgb(0, 0, 0), Color::Rgb(0, 0, 0)));
+    }
+
+    #[test]
+    fn braille() {
+        let area = Rect::new(0, 0, 1, 1);
+        let mut buf = Buffer::empty(area);
+        UnicodeImage::braille(&two_tone(Rgb([255, 255, 255]))).render(area, &mut buf);
+        // only the bottom two rows are dark
+        assert_eq!(buf.get(0, 0).symbol(), "⣤");
+    }
+}
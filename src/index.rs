@@ -0,0 +1,222 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use lopdf::Document;
+use serde::{Deserialize, Serialize};
+use crate::pdf::decrypt_document;
+use crate::search::{build_matcher, find_matches, SearchMatch};
+use crate::text::{extract_page_text, is_cjk, PageText};
+
+/// File name of the index, inside the `<pdf>-rpr` directory
+pub const INDEX_FILE: &str = "index.json";
+/// Bumped whenever the stored layout or the text extraction changes
+const INDEX_VERSION: u32 = 5;
+
+/// Size and modification time of the pdf the index was built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceStamp {
+    len: u64,
+    modified: u64,
+}
+
+impl SourceStamp {
+    pub fn of(pdf_path: &str) -> anyhow::Result<Self> {
+        let metadata = fs::metadata(pdf_path)?;
+        let modified = metadata.modified()?.duration_since(UNIX_EPOCH)?.as_secs();
+        Ok(Self { len: metadata.len(), modified })
+    }
+}
+
+/// Full-text index of a document: the text of every page plus term => (page, byte offset) postings.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    source: SourceStamp,
+    pages: Vec<PageText>,
+    terms: BTreeMap<String, Vec<(u32, u32)>>,
+}
+
+impl SearchIndex {
+    /// Index the texts of all pages, `pages[0]` being page 1.
+    pub fn build(pages: Vec<PageText>, source: SourceStamp) -> Self {
+        let mut terms: BTreeMap<String, Vec<(u32, u32)>> = BTreeMap::new();
+        for (index, page) in pages.iter().enumerate() {
+            for (offset, term) in tokenize(&page.text) {
+                terms.entry(term).or_default().push((index as u32 + 1, offset as u32));
+            }
+        }
+        Self { version: INDEX_VERSION, source, pages, terms }
+    }
+
+    /// Extract and index the whole document, decrypting it with `password` if given.
+    pub fn build_from_pdf(pdf_path: &str, password: Option<&str>) -> anyhow::Result<Self> {
+        let source = SourceStamp::of(pdf_path)?;
+        let mut document = Document::load(pdf_path)?;
+        if document.is_encrypted() {
+            decrypt_document(&mut document, password.unwrap_or_default())?;
+        }
+        let pages = document.page_iter().map(|page_id| extract_page_text(&document, page_id)).collect();
+        Ok(Self::build(pages, source))
+    }
+
+    /// The stored index, None when missing or built from another version of the pdf.
+    pub fn load(path: &Path, source: SourceStamp) -> Option<Self> {
+        let data = fs::read(path).ok()?;
+        let index: Self = serde_json::from_slice(&data).ok()?;
+        (index.version == INDEX_VERSION && index.source == source).then_some(index)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Load the index stored at `path`, or build and store it when it is stale.
+    /// The text of a password protected pdf is never written to disk.
+    pub fn open(pdf_path: &str, path: &Path, password: Option<&str>) -> anyhow::Result<Self> {
+        if password.is_some() {
+            return Self::build_from_pdf(pdf_path, password);
+        }
+        let source = SourceStamp::of(pdf_path)?;
+        if let Some(index) = Self::load(path, source) {
+            return Ok(index);
+        }
+        let index = Self::build_from_pdf(pdf_path, None)?;
+        index.save(path)?;
+        Ok(index)
+    }
+
+    pub fn page(&self, page_id: u32) -> Option<&PageText> {
+        self.pages.get(page_id.checked_sub(1)? as usize)
+    }
+
+    /// Hits of `query` in page order, only pages holding every word of a literal query are scanned.
+    pub fn search(&self, query: &str, is_regex: bool) -> anyhow::Result<Vec<SearchMatch>> {
+        let matcher = build_matcher(query, is_regex)?;
+        let pages: Vec<u32> = match is_regex {
+            true => (1..=self.pages.len() as u32).collect(),
+            false => self.candidate_pages(query),
+        };
+        Ok(pages.into_iter()
+            .filter_map(|page_id| Some((page_id, self.page(page_id)?)))
+            .flat_map(|(page_id, page)| find_matches(&matcher, page_id, &page.text))
+            .collect())
+    }
+
+    /// Pages that may hold `query`, every page with a hit included.
+    ///
+    /// Where `query` occurs its first word ends a word of the text, the words
+    /// in between are whole words and the last one starts a word; a single
+    /// word can be anywhere inside a longer one.
+    fn candidate_pages(&self, query: &str) -> Vec<u32> {
+        let words = tokenize(query);
+        let mut candidates: Option<BTreeSet<u32>> = None;
+        for (index, (_, word)) in words.iter().enumerate() {
+            let (first, last) = (index == 0, index + 1 == words.len());
+            let postings: Vec<&Vec<(u32, u32)>> = match (first, last) {
+                (true, true) => self.terms.iter().filter(|(term, _)| term.contains(word.as_str())).map(|(_, postings)| postings).collect(),
+                (true, false) => self.terms.iter().filter(|(term, _)| term.ends_with(word.as_str())).map(|(_, postings)| postings).collect(),
+                (false, true) => self.terms.range::<str, _>((Bound::Included(word.as_str()), Bound::Unbounded))
+                    .take_while(|(term, _)| term.starts_with(word.as_str()))
+                    .map(|(_, postings)| postings)
+                    .collect(),
+                (false, false) => self.terms.get(word).into_iter().collect(),
+            };
+            let pages: BTreeSet<u32> = postings.into_iter().flatten().map(|&(page_id, _)| page_id).collect();
+            candidates = Some(match candidates {
+                Some(candidates) => candidates.intersection(&pages).copied().collect(),
+                None => pages,
+            });
+        }
+        match candidates {
+            Some(candidates) => candidates.into_iter().collect(),
+            // 没有可索引的词, 全部扫描
+            None => (1..=self.pages.len() as u32).collect(),
+        }
+    }
+}
+
+/// Lowercase words with their byte offset, every CJK character being a word of its own.
+fn tokenize(text: &str) -> Vec<(usize, String)> {
+    let mut terms = vec![];
+    let mut word: Option<(usize, String)> = None;
+    for (offset, c) in text.char_indices() {
+        if is_cjk(c) {
+            terms.extend(word.take());
+            terms.push((offset, c.to_string()));
+        } else if c.is_alphanumeric() {
+            word.get_or_insert_with(|| (offset, String::new())).1.extend(c.to_lowercase());
+        } else {
+            terms.extend(word.take());
+        }
+    }
+    terms.extend(word);
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::search::{build_matcher, find_matches, SearchMatch};
+    use crate::text::PageText;
+    use super::{tokenize, SearchIndex, SourceStamp};
+
+    fn index(texts: &[&str]) -> SearchIndex {
+        let pages = texts.iter().map(|text| PageText { text: text.to_string(), spans: vec![] }).collect();
+        SearchIndex::build(pages, SourceStamp { len: 1, modified: 1 })
+    }
+
+    #[test]
+    fn tokenize_words_and_cjk() {
+        assert_eq!(tokenize("Hello, 世界x2"), vec![
+            (0, "hello".to_string()), (7, "世".to_string()), (10, "界".to_string()), (13, "x2".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn literal_search_uses_candidates() {
+        let index = index(&["the MIME database", "nothing here", "mime types and database"]);
+        assert_eq!(index.candidate_pages("mime data"), vec![1, 3]);
+        assert_eq!(index.candidate_pages("types"), vec![3]);
+        assert_eq!(index.candidate_pages("ime"), vec![1, 3]);
+        assert_eq!(index.candidate_pages("ime data"), vec![1, 3]);
+        assert_eq!(index.candidate_pages("me types an"), vec![3]);
+        assert_eq!(index.candidate_pages("mime base"), Vec::<u32>::new());
+        let hits = index.search("mime", false).unwrap();
+        assert_eq!(hits.iter().map(|hit| hit.page).collect::<Vec<_>>(), vec![1, 3]);
+        let hits = index.search(r"th\w+", true).unwrap();
+        assert_eq!(hits.iter().map(|hit| hit.page).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    /// The index only narrows the pages down, the hits are those of a scan of every page.
+    #[test]
+    fn indexed_search_matches_full_scan() {
+        let texts = [
+            "The MIME database, mime-types and x-mime", "nothing here", "Database types: 数据库 and databases",
+            "sub-types of a mime type", "ABC123 def", "",
+        ];
+        let index = index(&texts);
+        let queries = ["mime", "ime", "IME DATA", "me-ty", "ase", "types and", "数据", "据库 an", "c12", "3 de", "e, m", "-", "nope"];
+        for query in queries {
+            let matcher = build_matcher(query, false).unwrap();
+            let scanned: Vec<SearchMatch> = texts.iter().enumerate()
+                .flat_map(|(page, text)| find_matches(&matcher, page as u32 + 1, text))
+                .collect();
+            assert_eq!(index.search(query, false).unwrap(), scanned, "{query}");
+        }
+    }
+
+    #[test]
+    fn stale_index_is_not_loaded() {
+        let dir = std::env::temp_dir().join(format!("rpr-index-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(super::INDEX_FILE);
+        index(&["page"]).save(&path).unwrap();
+        assert!(SearchIndex::load(&path, SourceStamp { len: 1, modified: 1 }).is_some());
+        assert!(SearchIndex::load(&path, SourceStamp { len: 2, modified: 1 }).is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
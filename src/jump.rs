@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+/// Most pages remembered, the oldest are dropped first
+const MAX_JUMPS: usize = 100;
+
+/// Pages left and reached by non-sequential page changes, walked back and forth like browser history.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JumpList {
+    pages: Vec<u32>,
+    /// position of the page being read in `pages`
+    index: usize,
+}
+
+impl JumpList {
+    /// Record a jump from page `from` to page `to`, forgetting the pages ahead of the current one.
+    pub fn push(&mut self, from: u32, to: u32) {
+        // 0 is before the first page has been shown
+        if from == to || from == 0 {
+            return;
+        }
+        self.pages.truncate(self.index + 1);
+        match self.pages.last_mut() {
+            Some(last) => *last = from,
+            None => self.pages.push(from),
+        }
+        self.pages.push(to);
+        if self.pages.len() > MAX_JUMPS {
+            self.pages.drain(..self.pages.len() - MAX_JUMPS);
+        }
+        self.index = self.pages.len() - 1;
+    }
+
+    /// The page before the last jump, `current` being remembered to come back to.
+    pub fn back(&mut self, current: u32) -> Option<u32> {
+        if self.index == 0 || self.index >= self.pages.len() {
+            return None;
+        }
+        self.pages[self.index] = current;
+        self.index -= 1;
+        Some(self.pages[self.index])
+    }
+
+    /// The page left by going back.
+    pub fn forward(&mut self, current: u32) -> Option<u32> {
+        if self.index + 1 >= self.pages.len() {
+            return None;
+        }
+        self.pages[self.index] = current;
+        self.index += 1;
+        Some(self.pages[self.index])
+    }
+
+    /// Forget everything when a page is past the end, the document having changed.
+    pub fn reset_if_beyond(&mut self, page_nums: u32) {
+        if self.pages.iter().any(|&page| page == 0 || page > page_nums) {
+            *self = Self::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JumpList;
+
+    #[test]
+    fn back_and_forward() {
+        let mut jumps = JumpList::default();
+        assert_eq!(jumps.back(1), None);
+        jumps.push(1, 40);
+        jumps.push(42, 7);
+        assert_eq!(jumps.back(9), Some(42));
+        assert_eq!(jumps.back(42), Some(1));
+        assert_eq!(jumps.back(1), None);
+        assert_eq!(jumps.forward(3), Some(42));
+        assert_eq!(jumps.forward(42), Some(9));
+        assert_eq!(jumps.forward(9), None);
+        // a new jump drops the pages ahead
+        assert_eq!(jumps.back(9), Some(42));
+        jumps.push(43, 100);
+        assert_eq!(jumps.forward(100), None);
+        assert_eq!(jumps.back(100), Some(43));
+        assert_eq!(jumps.back(43), Some(3));
+    }
+}
@@ -21,6 +21,9 @@ pub mod handler;
 /// PDF handler
 pub mod pdf;
 
+/// typed errors of pdf, cache and history
+pub mod error;
+
 /// character decode
 pub mod decode;
 
@@ -34,6 +37,30 @@ pub mod ro_cell;
 /// PDF file read history
 pub mod history;
 
+/// pdf page to image converters
+pub mod rasterizer;
+
+/// page text extraction
+pub mod text;
+
+/// full-text search
+pub mod search;
+
+/// persistent full-text search index
+pub mod index;
+
+/// OSC 52 clipboard
+pub mod clipboard;
+
+/// document info and XMP metadata
+pub mod metadata;
+
+/// page labels
+pub mod page_label;
+
+/// back/forward jump list
+pub mod jump;
+
 pub fn write_log(log: String) {
     fs::write(Path::new("./pdf.log"), log).unwrap();
 }
@@ -42,16 +69,15 @@ pub fn write_log(log: String) {
 #[cfg(test)]
 mod tests {
     use crate::pdf::PdfHandler;
-    use super::*;
 
     #[test]
     fn book_mark_test() {
-        let pdf_handler = PdfHandler::new("/Users/zlatan/Documents/电子书/rust-book-zh-cn-shieber.pdf");
+        let _pdf_handler = PdfHandler::new("/Users/zlatan/Documents/电子书/rust-book-zh-cn-shieber.pdf", None).unwrap();
     }
 
     #[test]
     fn vec_test() {
-        let a = vec![3; 5];
+        let a = [3; 5];
         let b = &a[0..];
         println!("{b:?}");
     }
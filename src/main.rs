@@ -2,13 +2,16 @@ use pdf_terminal_reader::app::{App};
 use pdf_terminal_reader::event::{Event, EventHandler};
 use pdf_terminal_reader::handler::handle_key_events;
 use pdf_terminal_reader::tui::Tui;
-use std::io;
-use anyhow::bail;
+use std::{fs, io};
+use std::path::PathBuf;
+use anyhow::{bail, Context};
 use clap::Parser;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use pdf_terminal_reader::{emit};
 use pdf_terminal_reader::history::History;
+use pdf_terminal_reader::image::GraphicsProtocol;
+use pdf_terminal_reader::rasterizer::RasterizerKind;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -17,12 +20,27 @@ pub struct AppArgs {
     /// if None => last read pdf
     // #[arg(short, long)]
     path: Option<String>,
+    /// terminal graphics protocol
+    /// if None => detect from the terminal
+    #[arg(short, long, value_enum)]
+    graphics: Option<GraphicsProtocol>,
+    /// tool converting pdf pages to images, remembered for later runs
+    /// if None => the remembered one, else the first one found in PATH
+    #[arg(short, long, value_enum)]
+    rasterizer: Option<RasterizerKind>,
+    /// password of an encrypted pdf
+    /// if None => asked for when needed
+    #[arg(long, conflicts_with = "password_file")]
+    password: Option<String>,
+    /// file holding the password on its first line
+    #[arg(long)]
+    password_file: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = AppArgs::parse();
-    let mut history = History::init();
+    let mut history = History::init()?;
     let default_path = history.get_last_read_pdf();
     if default_path.is_none() && args.path.is_none() {
         bail!("please pass a pdf file path");
@@ -31,33 +49,68 @@ async fn main() -> anyhow::Result<()> {
         Some(path) => path,
         None => default_path.as_ref().unwrap()
     };
-    let mut app = App::new(pdf_path, history.read_last_page_num(pdf_path).unwrap_or(0));
+    let rasterizer = match args.rasterizer {
+        Some(kind) => {
+            history.set_rasterizer(kind);
+            Some(kind.build()?)
+        }
+        // the remembered one may have been uninstalled since
+        None => history.read_rasterizer().and_then(|kind| kind.build().ok()),
+    };
+    let rasterizer = match rasterizer {
+        Some(rasterizer) => rasterizer,
+        None => match RasterizerKind::detect() {
+            Some(kind) => kind.build()?,
+            None => bail!("no pdf rasterizer found in PATH, install one of poppler (pdftoppm, pdftocairo), mupdf-tools (mutool) or ghostscript (gs)"),
+        },
+    };
+    let password = match &args.password_file {
+        Some(path) => {
+            let content = fs::read_to_string(path).with_context(|| format!("can't read the password file {}", path.display()))?;
+            Some(content.lines().next().unwrap_or_default().to_string())
+        }
+        None => args.password.clone(),
+    };
+    let mut app = App::new(pdf_path, history.read_last_page_num(pdf_path).unwrap_or(0), rasterizer, password.as_deref())?;
+    if let Some(zoom) = history.read_zoom(pdf_path) {
+        app.set_zoom(zoom);
+    }
+    if let Some(mut jumps) = history.read_jumps(pdf_path) {
+        jumps.reset_if_beyond(app.pdf_handler.get_page_nums() as u32);
+        app.jump_list = jumps;
+    }
 
     let backend = CrosstermBackend::new(io::stderr());
     let terminal = Terminal::new(backend)?;
-    let events = EventHandler::new(100000);
-    let mut tui = Tui::new(terminal, events);
-    tui.init()?;
+    let mut tui = Tui::new(terminal);
+    let graphics = tui.init(args.graphics)?;
+    app.image_handler.set_protocol(graphics.protocol);
+    app.image_handler.set_cell_size(graphics.cell_size);
+    let mut events = EventHandler::new(100000);
+    app.start_indexing();
 
     // Start the main loop.
     while app.running {
         // Render the user interface.
         tui.draw(&mut app)?;
         // Handle events.
-        let event = tui.events.next().await?;
+        let event = events.next().await?;
         match event {
             Event::Tick => app.tick(),
             Event::Key(key_event) => handle_key_events(key_event, &mut app)?,
             // Event::Mouse(_) => {}
-            Event::Resize(_, _) => {}
+            Event::Resize(_, _) => {
+                app.image_handler.refresh_after_resize();
+                // the preview pane changed size, render the page for it again
+                app.already_render = false;
+            }
+            // 文本模式下不画图片
+            Event::RenderPdf if app.text_mode => {}
             Event::RenderPdf => {
-                if !app.page_cache.page_exists(app.cur_page).await {
-                    emit!(LoadingFirst(app.cur_page));
-                    continue;
-                }
-                match app.page_cache.load_page_data(app.cur_page) {
-                    Ok(data) => {
-                        app.image_handler.render_image(&data,
+                // None => a page is still being converted, RenderPdf comes again once it is
+                match app.page_image().await {
+                    Ok(Some(image)) => {
+                        app.image_handler.render_image(&image,
                                                        &app.pdf_size)?;
                         app.loading = false;
                         // 继续静默加载
@@ -65,13 +118,29 @@ async fn main() -> anyhow::Result<()> {
                             emit!(LoadingNext);
                         }
                     }
+                    Ok(None) => {}
+                    // a broken cached page, tell instead of quitting
                     Err(e) => {
-                        if let io::ErrorKind::NotFound = e.kind() {
-                            emit!(LoadingFirst(app.cur_page));
-                        }
+                        app.loading = false;
+                        app.status = Some(format!("{e:#}"));
                     }
                 }
             }
+            Event::RenderFailed(page_id, message) => {
+                app.page_cache.set_failed(page_id);
+                app.loading = false;
+                app.status = Some(message);
+                // 继续加载后面的页
+                if app.next_load_page <= app.pdf_handler.get_page_nums() as u32 {
+                    emit!(LoadingNext);
+                }
+            }
+            // 缩略图失败不影响该页本身, 标记失败并提示
+            Event::ThumbnailFailed(page_id, message) => {
+                app.page_cache.set_thumbnail_failed(page_id);
+                app.status = Some(message);
+                emit!(RenderPdf);
+            }
             Event::LoadingFirst(page_id) => {
                 app.page_cache.add_first(page_id).await;
             }
@@ -87,10 +156,19 @@ async fn main() -> anyhow::Result<()> {
             Event::ChangeState(state) => {
                 app.app_state = state;
             }
+            Event::ClearScreen => {
+                app.image_handler.clear()?;
+                tui.clear()?;
+            }
+            Event::IndexReady(index) => {
+                app.search_index = Some(index);
+            }
         }
     }
     // Exit the user interface.
     tui.exit()?;
-    history.save_history(pdf_path, app.cur_page);
+    history.set_zoom(pdf_path, app.pdf_size.zoom());
+    history.set_jumps(pdf_path, &app.jump_list);
+    history.save_history(pdf_path, app.reading_page())?;
     Ok(())
 }
@@ -0,0 +1,213 @@
+use lopdf::{Dictionary, Document, Object};
+use crate::decode::decode_text_string;
+
+/// Document information from the `/Info` dictionary and the XMP metadata stream.
+///
+/// XMP is preferred where both have a value: it is always UTF-8, while
+/// `/Info` strings are often in some local charset.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub creation_date: Option<String>,
+    pub mod_date: Option<String>,
+    /// PDF version, the catalog's /Version wins over the file header
+    pub version: String,
+    pub page_count: usize,
+}
+
+impl Metadata {
+    pub fn parse(document: &Document) -> Self {
+        let info = document.trailer.get(b"Info").ok()
+            .and_then(|object| document.dereference(object).ok())
+            .and_then(|(_, object)| object.as_dict().ok());
+        let catalog = document.catalog().ok();
+        let xmp = catalog
+            .and_then(|catalog| catalog.get(b"Metadata").ok())
+            .and_then(|object| document.dereference(object).ok())
+            .and_then(|(_, object)| object.as_stream().ok())
+            .map(|stream| stream.decompressed_content().unwrap_or_else(|_| stream.content.clone()))
+            .map(|data| String::from_utf8_lossy(&data).into_owned())
+            .unwrap_or_default();
+        let info_text = |key: &[u8]| info.and_then(|info| info_string(info, key));
+        let version = catalog
+            .and_then(|catalog| catalog.get(b"Version").and_then(Object::as_name_str).ok())
+            .filter(|version| *version > document.version.as_str())
+            .unwrap_or(&document.version)
+            .to_string();
+        Self {
+            title: xmp_value(&xmp, "dc:title").or_else(|| info_text(b"Title")),
+            author: xmp_value(&xmp, "dc:creator").or_else(|| info_text(b"Author")),
+            subject: xmp_value(&xmp, "dc:description").or_else(|| info_text(b"Subject")),
+            keywords: xmp_value(&xmp, "pdf:Keywords").or_else(|| info_text(b"Keywords")),
+            creator: xmp_value(&xmp, "xmp:CreatorTool").or_else(|| info_text(b"Creator")),
+            producer: xmp_value(&xmp, "pdf:Producer").or_else(|| info_text(b"Producer")),
+            creation_date: xmp_value(&xmp, "xmp:CreateDate").map(|date| date.replace('T', " "))
+                .or_else(|| info_text(b"CreationDate").map(|date| format_pdf_date(&date))),
+            mod_date: xmp_value(&xmp, "xmp:ModifyDate").map(|date| date.replace('T', " "))
+                .or_else(|| info_text(b"ModDate").map(|date| format_pdf_date(&date))),
+            version,
+            page_count: document.get_pages().len(),
+        }
+    }
+
+    /// (label, value) rows of the known fields.
+    pub fn rows(&self) -> Vec<(&'static str, String)> {
+        let mut rows = vec![];
+        let fields = [
+            ("标题", &self.title),
+            ("作者", &self.author),
+            ("主题", &self.subject),
+            ("关键词", &self.keywords),
+            ("创建工具", &self.creator),
+            ("生成工具", &self.producer),
+            ("创建时间", &self.creation_date),
+            ("修改时间", &self.mod_date),
+        ];
+        for (label, value) in fields {
+            if let Some(value) = value {
+                rows.push((label, value.clone()));
+            }
+        }
+        rows.push(("页数", self.page_count.to_string()));
+        rows.push(("PDF 版本", self.version.clone()));
+        rows
+    }
+}
+
+fn info_string(info: &Dictionary, key: &[u8]) -> Option<String> {
+    match info.get(key).ok()? {
+        Object::String(bytes, _) => Some(decode_text_string(bytes).trim().to_string()).filter(|s| !s.is_empty()),
+        _ => None,
+    }
+}
+
+/// `D:YYYYMMDDHHmmSSOHH'mm'` => `YYYY-MM-DD HH:mm:SS+HH:mm`, missing parts left out.
+pub fn format_pdf_date(date: &str) -> String {
+    let date = date.strip_prefix("D:").unwrap_or(date);
+    let digits: String = date.chars().take_while(char::is_ascii_digit).collect();
+    if digits.len() < 4 {
+        return date.to_string();
+    }
+    let part = |range: std::ops::Range<usize>| digits.get(range);
+    let mut formatted = digits[..4].to_string();
+    for (range, separator) in [(4..6, "-"), (6..8, "-"), (8..10, " "), (10..12, ":"), (12..14, ":")] {
+        match part(range) {
+            Some(value) => {
+                formatted.push_str(separator);
+                formatted.push_str(value);
+            }
+            None => break,
+        }
+    }
+    let zone = &date[digits.len()..];
+    match zone.chars().next() {
+        Some('Z') => formatted.push_str(" UTC"),
+        Some(sign @ ('+' | '-')) => {
+            let zone: String = zone[1..].chars().filter(char::is_ascii_digit).collect();
+            if zone.len() >= 4 {
+                formatted.push_str(&format!("{sign}{}:{}", &zone[..2], &zone[2..4]));
+            } else if !zone.is_empty() {
+                formatted.push_str(&format!("{sign}{zone}"));
+            }
+        }
+        _ => {}
+    }
+    formatted
+}
+
+/// Text of the `tag` property in an XMP packet, as an element or as an attribute of
+/// `rdf:Description`. Of an `rdf:Alt` the first language is taken, Seq/Bag items are joined.
+pub fn xmp_value(xmp: &str, tag: &str) -> Option<String> {
+    let value = if let Some(start) = xmp.find(&format!("<{tag}>")).or_else(|| xmp.find(&format!("<{tag} "))) {
+        let content_start = start + xmp[start..].find('>')? + 1;
+        let content = &xmp[content_start..content_start + xmp[content_start..].find(&format!("</{tag}>"))?];
+        let mut items = vec![];
+        let mut rest = content;
+        while let Some(li) = rest.find("<rdf:li") {
+            let li_start = li + rest[li..].find('>')? + 1;
+            let li_end = li_start + rest[li_start..].find("</rdf:li>")?;
+            items.push(unescape_xml(rest[li_start..li_end].trim()));
+            rest = &rest[li_end..];
+        }
+        match items.is_empty() {
+            true => unescape_xml(content.trim()),
+            false if content.contains("<rdf:Alt") => items.swap_remove(0),
+            false => items.join(", "),
+        }
+    } else {
+        let attribute = format!("{tag}=\"");
+        let start = xmp.find(&attribute)? + attribute.len();
+        unescape_xml(xmp[start..start + xmp[start..].find('"')?].trim())
+    };
+    Some(value).filter(|value| !value.is_empty())
+}
+
+fn unescape_xml(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity.strip_prefix("#x").map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_pdf_date, xmp_value};
+
+    #[test]
+    fn pdf_dates() {
+        assert_eq!(format_pdf_date("D:20181002123456+02'00'"), "2018-10-02 12:34:56+02:00");
+        assert_eq!(format_pdf_date("D:20240101093000Z"), "2024-01-01 09:30:00 UTC");
+        assert_eq!(format_pdf_date("D:2019"), "2019");
+        assert_eq!(format_pdf_date("unknown"), "unknown");
+    }
+
+    #[test]
+    fn xmp_properties() {
+        let xmp = r#"<x:xmpmeta><rdf:RDF><rdf:Description rdf:about="" pdf:Producer="pdfTeX &amp; friends">
+            <dc:title><rdf:Alt><rdf:li xml:lang="x-default">数据手册 &lt;v2&gt;</rdf:li></rdf:Alt></dc:title>
+            <dc:creator><rdf:Seq><rdf:li>Ann</rdf:li><rdf:li>Bob</rdf:li></rdf:Seq></dc:creator>
+            <xmp:CreateDate>2020-05-01T08:00:00+08:00</xmp:CreateDate>
+            <pdf:Keywords></pdf:Keywords>
+        </rdf:Description></rdf:RDF></x:xmpmeta>"#;
+        assert_eq!(xmp_value(xmp, "dc:title").as_deref(), Some("数据手册 <v2>"));
+        assert_eq!(xmp_value(xmp, "dc:creator").as_deref(), Some("Ann, Bob"));
+        assert_eq!(xmp_value(xmp, "pdf:Producer").as_deref(), Some("pdfTeX & friends"));
+        assert_eq!(xmp_value(xmp, "xmp:CreateDate").as_deref(), Some("2020-05-01T08:00:00+08:00"));
+        assert_eq!(xmp_value(xmp, "pdf:Keywords"), None);
+        assert_eq!(xmp_value(xmp, "dc:description"), None);
+    }
+}
@@ -0,0 +1,166 @@
+use lopdf::{Dictionary, Document, Object};
+use crate::decode::decode_text_string;
+
+/// 页码标签的编号样式 (`/S`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelStyle {
+    /// 1, 2, 3
+    Decimal,
+    /// I, II, III
+    UpperRoman,
+    /// i, ii, iii
+    LowerRoman,
+    /// A .. Z, AA .. ZZ
+    UpperLetters,
+    /// a .. z, aa .. zz
+    LowerLetters,
+}
+
+/// 自 `start` (从 0 开始的页下标) 起的一段页码
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelRange {
+    pub start: usize,
+    pub style: Option<LabelStyle>,
+    pub prefix: String,
+    /// 段内第一页的编号
+    pub first: u32,
+}
+
+impl LabelRange {
+    fn parse(document: &Document, start: usize, dict: &Dictionary) -> Self {
+        let style = match dict.get(b"S").and_then(Object::as_name).ok() {
+            Some(b"D") => Some(LabelStyle::Decimal),
+            Some(b"R") => Some(LabelStyle::UpperRoman),
+            Some(b"r") => Some(LabelStyle::LowerRoman),
+            Some(b"A") => Some(LabelStyle::UpperLetters),
+            Some(b"a") => Some(LabelStyle::LowerLetters),
+            _ => None,
+        };
+        let prefix = match dict.get(b"P").and_then(|o| document.dereference(o)) {
+            Ok((_, Object::String(bytes, _))) => decode_text_string(bytes),
+            _ => String::new(),
+        };
+        let first = dict.get(b"St").and_then(Object::as_i64).unwrap_or(1).max(1) as u32;
+        Self { start, style, prefix, first }
+    }
+}
+
+/// 各页的页码标签, 文档没有 /PageLabels 时为空
+pub fn parse_page_labels(document: &Document, page_nums: usize) -> Vec<String> {
+    let Some(tree) = document.catalog().ok()
+        .and_then(|catalog| catalog.get(b"PageLabels").ok())
+        .and_then(|o| document.dereference(o).ok())
+        .and_then(|(_, o)| o.as_dict().ok()) else {
+        return vec![];
+    };
+    let mut ranges = vec![];
+    collect_ranges(document, tree, &mut ranges, 0);
+    ranges.sort_by_key(|range| range.start);
+    labels(&ranges, page_nums)
+}
+
+/// 遍历数字树, 防止循环引用限制深度
+fn collect_ranges(document: &Document, node: &Dictionary, ranges: &mut Vec<LabelRange>, depth: u32) {
+    if depth > 32 {
+        return;
+    }
+    if let Ok(nums) = node.get(b"Nums").and_then(Object::as_array) {
+        for pair in nums.chunks_exact(2) {
+            let start = pair[0].as_i64().ok().filter(|&start| start >= 0);
+            let dict = document.dereference(&pair[1]).ok().and_then(|(_, o)| o.as_dict().ok());
+            if let (Some(start), Some(dict)) = (start, dict) {
+                ranges.push(LabelRange::parse(document, start as usize, dict));
+            }
+        }
+    }
+    if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            if let Some(kid) = document.dereference(kid).ok().and_then(|(_, o)| o.as_dict().ok()) {
+                collect_ranges(document, kid, ranges, depth + 1);
+            }
+        }
+    }
+}
+
+/// 按起始页排好序的各段展开为每页的标签, 第一段之前的页没有编号
+pub fn labels(ranges: &[LabelRange], page_nums: usize) -> Vec<String> {
+    if ranges.is_empty() {
+        return vec![];
+    }
+    let mut labels = Vec::with_capacity(page_nums);
+    for index in 0..page_nums {
+        let label = match ranges.iter().rev().find(|range| range.start <= index) {
+            Some(range) => {
+                let number = range.first.saturating_add((index - range.start) as u32);
+                let number = match range.style {
+                    Some(style) => format_number(number, style),
+                    None => String::new(),
+                };
+                format!("{}{number}", range.prefix)
+            }
+            None => String::new(),
+        };
+        labels.push(label);
+    }
+    labels
+}
+
+pub fn format_number(number: u32, style: LabelStyle) -> String {
+    match style {
+        LabelStyle::Decimal => number.to_string(),
+        LabelStyle::UpperRoman => roman(number),
+        LabelStyle::LowerRoman => roman(number).to_lowercase(),
+        LabelStyle::UpperLetters => letters(number),
+        LabelStyle::LowerLetters => letters(number).to_lowercase(),
+    }
+}
+
+fn roman(mut number: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"),
+        (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+    ];
+    let mut result = String::new();
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            result.push_str(numeral);
+            number -= value;
+        }
+    }
+    result
+}
+
+/// 1 => A, 26 => Z, 27 => AA, 53 => AAA
+fn letters(number: u32) -> String {
+    if number == 0 {
+        return String::new();
+    }
+    let letter = char::from(b'A' + ((number - 1) % 26) as u8);
+    letter.to_string().repeat(((number - 1) / 26 + 1) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_number, labels, LabelRange, LabelStyle};
+
+    #[test]
+    fn number_styles() {
+        assert_eq!(format_number(1994, LabelStyle::UpperRoman), "MCMXCIV");
+        assert_eq!(format_number(12, LabelStyle::LowerRoman), "xii");
+        assert_eq!(format_number(1, LabelStyle::UpperLetters), "A");
+        assert_eq!(format_number(28, LabelStyle::LowerLetters), "bb");
+    }
+
+    #[test]
+    fn ranges_to_labels() {
+        let range = |start, style, prefix: &str, first| LabelRange { start, style, prefix: prefix.to_string(), first };
+        let ranges = [
+            range(1, Some(LabelStyle::LowerRoman), "", 1),
+            range(3, Some(LabelStyle::Decimal), "", 1),
+            range(5, Some(LabelStyle::Decimal), "A-", 3),
+            range(6, None, "封底", 1),
+        ];
+        assert_eq!(labels(&ranges, 7), vec!["", "i", "ii", "1", "2", "A-3", "封底"]);
+        assert!(labels(&[], 3).is_empty());
+    }
+}
@@ -1,12 +1,19 @@
-use std::cell::RefCell;
-use std::collections::BTreeMap;
-use std::ffi::OsString;
+use std::cell::{OnceCell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
 use std::ops::Index;
 use std::path::Path;
 use std::rc::Rc;
-use lopdf::{Document, Object, ObjectId, Outline};
+use std::str::FromStr;
+use anyhow::bail;
+use lopdf::{encryption, Dictionary, Document, Object, ObjectId};
+use lopdf::encryption::DecryptionError;
 use ratatui::prelude::Rect;
-use crate::decode::decode_str_to_utf8;
+use crate::decode::decode_text_string;
+use crate::error::Error;
+use crate::metadata::Metadata;
+use crate::page_label::parse_page_labels;
+use crate::text::{extract_page_text, PageText};
 
 pub type BookMarkType = Rc<RefCell<BookMark>>;
 
@@ -19,8 +26,27 @@ pub struct BookMarkIndex {
 pub struct PdfSize {
     x: u16,
     y: u16,
+    // 预览区像素大小
     width: i32,
     height: i32,
+    // 缩放模式
+    zoom: ZoomMode,
+    // 并排显示的页数
+    columns: u32,
+}
+
+/// 缩放模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZoomMode {
+    /// 整页放入预览区
+    #[default]
+    FitPage,
+    /// 页宽等于预览区宽度
+    FitWidth,
+    /// 页高等于预览区高度
+    FitHeight,
+    /// 固定百分比, 100% 为 96 DPI
+    Fixed(u32),
 }
 
 #[derive(Debug)]
@@ -28,6 +54,8 @@ pub struct PdfHandler {
     document: Document,
     // 页映射 对象Id => 页数
     page_map: BTreeMap<ObjectId, u32>,
+    // 命名目标 名称 => 页数
+    named_dests: BTreeMap<Vec<u8>, u32>,
     // 解析后的书签集合
     book_marks: Vec<BookMarkType>,
     // 无子目录的书签集合
@@ -38,6 +66,33 @@ pub struct PdfHandler {
     page_nums: usize,
     // pdf 书籍标题
     title: String,
+    // 文档信息
+    metadata: Metadata,
+    // 每页的页码标签, 没有 /PageLabels 时为空
+    page_labels: Vec<String>,
+    // 每页大小 (宽, 高) 单位 pt
+    page_sizes: Vec<(f32, f32)>,
+    // 每页显示区域 [x0, y0, x1, y1] 及旋转角度
+    page_boxes: Vec<([f32; 4], i64)>,
+    // 每页文本, 用到时才提取
+    page_texts: Vec<OnceCell<PageText>>,
+    // 解析时跳过的内容, 启动后提示
+    warnings: Vec<String>,
+    // 加密文档的密码, 渲染时传给转换工具
+    password: Option<String>,
+    // 加密且还没有输入正确密码
+    locked: bool,
+    // 加密方式 lopdf 不支持 (AES), 只能交给转换工具显示页面
+    render_only: bool,
+}
+
+/// 页内链接
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageLink {
+    /// 链接区域 [x0, y0, x1, y1], 页面用户空间
+    pub rect: [f32; 4],
+    /// 目标页
+    pub target: u32,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -114,34 +169,108 @@ impl BookMark {
 }
 
 impl PdfHandler {
-    pub fn new(path: &str) -> Self {
-        let document = Document::load(path).expect("pdf file not found");
-        let title = OsString::from(Path::new(path).file_stem().unwrap()).into_string().unwrap();
-        // TODO parse title
-        // if let Ok(Object::Reference(info_id)) = document.trailer.get(b"Info") {
-        //     if let Ok(info) = document.get_dictionary(info_id.clone()) {
-        //         println!("{info:?}");
-        //     }
-        // }
+    /// 加密的文档先试 `password` 再试空密码, 都不对时为锁定状态, 等待 [`PdfHandler::unlock`]
+    pub fn new(path: &str, password: Option<&str>) -> Result<Self, Error> {
+        let document = Document::load(path).map_err(|source| Error::Pdf { path: path.to_string(), source })?;
+        Self::from_document(path, document, password)
+    }
+
+    /// 已读入的文档, `path` 为其文件路径
+    fn from_document(path: &str, mut document: Document, password: Option<&str>) -> Result<Self, Error> {
+        let pdf_error = |source| Error::Pdf { path: path.to_string(), source };
+        let mut locked = false;
+        let mut render_only = false;
+        let mut used_password = None;
+        if document.is_encrypted() {
+            // 只设置了所有者密码的文档用空密码即可打开
+            let password = password.into_iter().chain([""])
+                .map(|password| (password, decrypt_document(&mut document, password)))
+                .find(|(_, result)| !matches!(result, Err(lopdf::Error::Decryption(DecryptionError::IncorrectPassword))));
+            match password {
+                Some((password, Ok(()))) => used_password = Some(password.to_string()).filter(|password| !password.is_empty()),
+                // 无法验证密码, 原样交给转换工具
+                Some((password, Err(lopdf::Error::Decryption(DecryptionError::UnsupportedEncryption)))) => {
+                    render_only = true;
+                    used_password = Some(password.to_string()).filter(|password| !password.is_empty());
+                }
+                Some((_, Err(e))) => return Err(pdf_error(e)),
+                None => locked = true,
+            }
+        }
+        // 没有标题时用文件名
+        let title = Path::new(path).file_stem().unwrap_or(path.as_ref()).to_string_lossy().into_owned();
         let page_map: BTreeMap<ObjectId, u32> = document.page_iter().enumerate().map(|(i, p)| (p, i as u32 + 1)).collect();
         let page_nums = page_map.len();
+        let page_boxes: Vec<([f32; 4], i64)> = document.page_iter().map(|id| Self::parse_page_box(&document, id)).collect();
+        let page_sizes = page_boxes.iter().map(|&(rect, rotate)| Self::page_size(rect, rotate)).collect();
         let mut pdf_handler = Self {
             document,
             page_map,
+            named_dests: BTreeMap::new(),
             book_marks: vec![],
             pdf_path: path.to_string(),
             page_nums,
             title,
+            metadata: Metadata::default(),
+            page_labels: vec![],
             book_marks_pages: vec![],
+            page_sizes,
+            page_boxes,
+            // 未解密的内容提取不出文本
+            page_texts: (0..if render_only { 0 } else { page_nums }).map(|_| OnceCell::new()).collect(),
+            warnings: vec![],
+            password: used_password,
+            locked,
+            render_only,
         };
-        pdf_handler.init();
-        pdf_handler
+        if render_only {
+            pdf_handler.warnings.push(String::from("不支持的加密方式, 只显示页面, 没有文本, 目录和文档信息"));
+        } else if !locked {
+            pdf_handler.init();
+        }
+        Ok(pdf_handler)
     }
 
+    /// 解析文本相关的内容, 加密文档解密后才能进行
     fn init(&mut self) {
+        self.metadata = Metadata::parse(&self.document);
+        if let Some(title) = self.metadata.title.clone() {
+            self.title = title;
+        }
+        self.page_labels = parse_page_labels(&self.document, self.page_nums);
+        self.named_dests = self.parse_named_dests();
         self.parse_book_marks();
     }
 
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn is_render_only(&self) -> bool {
+        self.render_only
+    }
+
+    pub fn get_password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
+
+    /// 用密码解密锁定的文档, 返回密码是否正确
+    pub fn unlock(&mut self, password: &str) -> Result<bool, Error> {
+        if !self.locked {
+            return Ok(true);
+        }
+        match decrypt_document(&mut self.document, password) {
+            Ok(()) => {}
+            Err(lopdf::Error::Decryption(DecryptionError::IncorrectPassword)) => return Ok(false),
+            Err(source) => return Err(Error::Pdf { path: self.pdf_path.clone(), source }),
+        }
+        self.locked = false;
+        self.password = Some(password.to_string());
+        self.page_texts = (0..self.page_nums).map(|_| OnceCell::new()).collect();
+        self.init();
+        Ok(true)
+    }
+
     pub fn get_book_marks(&self) -> &Vec<BookMarkType> {
         &self.book_marks
     }
@@ -158,6 +287,205 @@ impl PdfHandler {
         &self.title
     }
 
+    pub fn get_warnings(&self) -> &Vec<String> {
+        &self.warnings
+    }
+
+    pub fn get_metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// 页码标签, 与页数相同时为 None
+    pub fn get_page_label(&self, page_num: u32) -> Option<&str> {
+        let label = self.page_labels.get(page_num.checked_sub(1)? as usize)?;
+        (!label.is_empty() && *label != page_num.to_string()).then_some(label.as_str())
+    }
+
+    /// 页码标签或页数对应的页, `#` 开头时只按页数查找
+    pub fn find_page(&self, input: &str) -> Option<u32> {
+        let input = input.trim();
+        let page_num = match input.strip_prefix('#') {
+            Some(num) => num.parse::<u32>().ok(),
+            None => self.page_labels.iter().position(|label| label == input)
+                .or_else(|| self.page_labels.iter().position(|label| label.eq_ignore_ascii_case(input)))
+                .map(|index| index as u32 + 1)
+                .or_else(|| input.parse::<u32>().ok()),
+        }?;
+        (page_num > 0 && page_num <= self.page_nums as u32).then_some(page_num)
+    }
+
+    pub fn get_page_sizes(&self) -> &Vec<(f32, f32)> {
+        &self.page_sizes
+    }
+
+    /// 一页的文本, 只提取这一页
+    pub fn get_page_text(&self, page_num: u32) -> Option<&PageText> {
+        let cell = self.page_texts.get(page_num.checked_sub(1)? as usize)?;
+        let page_id = self.document.page_iter().nth(page_num as usize - 1)?;
+        Some(cell.get_or_init(|| extract_page_text(&self.document, page_id)))
+    }
+
+    /// 所有页的文本, 按页序
+    pub fn get_page_texts(&self) -> impl Iterator<Item = &PageText> {
+        self.document.page_iter().zip(self.page_texts.iter())
+            .map(|(page_id, cell)| cell.get_or_init(|| extract_page_text(&self.document, page_id)))
+    }
+
+    /// 页面用户空间中的点在页图像 (`image_size` 像素) 上的位置
+    pub fn page_point_to_image(&self, page_num: u32, (x, y): (f32, f32), (width, height): (u32, u32)) -> (f32, f32) {
+        let Some(&([x0, y0, x1, y1], rotate)) = self.page_boxes.get(page_num.saturating_sub(1) as usize) else {
+            return (0.0, 0.0);
+        };
+        // 未旋转时自左上角起的比例坐标
+        let (u, v) = ((x - x0) / (x1 - x0), (y1 - y) / (y1 - y0));
+        let (u, v) = match rotate.rem_euclid(360) {
+            90 => (1.0 - v, u),
+            180 => (1.0 - u, 1.0 - v),
+            270 => (v, 1.0 - u),
+            _ => (u, v),
+        };
+        (u * width as f32, v * height as f32)
+    }
+
+    /// 页上指向本文档的链接, 按从上到下, 从左到右排序
+    pub fn get_page_links(&self, page_num: u32) -> Vec<PageLink> {
+        let Some(&page_id) = self.document.get_pages().get(&page_num) else {
+            return vec![];
+        };
+        let annots = self.document.get_dictionary(page_id).ok()
+            .and_then(|page| page.get(b"Annots").ok())
+            .and_then(|annots| self.document.dereference(annots).ok())
+            .and_then(|(_, annots)| annots.as_array().ok());
+        let mut links: Vec<PageLink> = annots.into_iter().flatten()
+            .filter_map(|annot| self.document.dereference(annot).ok()?.1.as_dict().ok())
+            .filter(|annot| matches!(annot.get(b"Subtype").and_then(Object::as_name), Ok(b"Link")))
+            .filter_map(|annot| {
+                let target = match annot.get(b"Dest") {
+                    Ok(dest) => self.resolve_dest(dest),
+                    Err(_) => self.resolve_action(annot.get(b"A").ok()?),
+                }?;
+                let rect = self.document.dereference(annot.get(b"Rect").ok()?).ok()?.1.as_array().ok()?;
+                let values: Vec<f32> = rect.iter().filter_map(|v| v.as_float().ok()).collect();
+                match values[..] {
+                    [x0, y0, x1, y1] => Some(PageLink { rect: [x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)], target }),
+                    _ => None,
+                }
+            })
+            .collect();
+        links.sort_by(|a, b| b.rect[3].total_cmp(&a.rect[3]).then(a.rect[0].total_cmp(&b.rect[0])));
+        links
+    }
+
+    /// GoTo 动作的目标页
+    fn resolve_action(&self, action: &Object) -> Option<u32> {
+        let action = self.document.dereference(action).ok()?.1.as_dict().ok()?;
+        match action.get(b"S").and_then(Object::as_name).ok()? {
+            b"GoTo" => self.resolve_dest(action.get(b"D").ok()?),
+            _ => None,
+        }
+    }
+
+    /// 目标 `[page /XYZ ...]` 或命名目标所在的页
+    fn resolve_dest(&self, dest: &Object) -> Option<u32> {
+        match self.document.dereference(dest).ok()?.1 {
+            Object::Name(name) | Object::String(name, _) => self.named_dests.get(name).copied(),
+            Object::Array(dest) => match dest.first()? {
+                Object::Reference(id) => self.page_map.get(id).copied(),
+                // 非法但常见: 从 0 开始的页下标
+                Object::Integer(index) => u32::try_from(*index).ok().map(|index| index + 1).filter(|&num| num as usize <= self.page_nums),
+                _ => None,
+            },
+            Object::Dictionary(dict) => self.resolve_dest(dict.get(b"D").ok()?),
+            _ => None,
+        }
+    }
+
+    /// 目录 /Dests 字典 (PDF 1.1) 与 /Names 中 /Dests 名称树里的命名目标
+    fn parse_named_dests(&self) -> BTreeMap<Vec<u8>, u32> {
+        let mut dests: Vec<(Vec<u8>, &Object)> = vec![];
+        let catalog = self.document.catalog().ok();
+        if let Some(old_dests) = self.child_dict(catalog, b"Dests") {
+            dests.extend(old_dests.iter().map(|(name, dest)| (name.clone(), dest)));
+        }
+        if let Some(tree) = self.child_dict(self.child_dict(catalog, b"Names"), b"Dests") {
+            Self::collect_name_tree(&self.document, tree, &mut dests, 0);
+        }
+        dests.into_iter()
+            .filter_map(|(name, dest)| Some((name, self.resolve_dest(dest)?)))
+            .collect()
+    }
+
+    fn child_dict<'a>(&'a self, parent: Option<&'a Dictionary>, key: &[u8]) -> Option<&'a Dictionary> {
+        match self.document.dereference(parent?.get(key).ok()?) {
+            Ok((_, Object::Dictionary(dict))) => Some(dict),
+            _ => None,
+        }
+    }
+
+    /// 遍历名称树, 防止循环引用限制深度
+    fn collect_name_tree<'a>(document: &'a Document, node: &'a Dictionary, entries: &mut Vec<(Vec<u8>, &'a Object)>, depth: u32) {
+        if depth > 32 {
+            return;
+        }
+        if let Ok(names) = node.get(b"Names").and_then(|o| document.dereference(o)).and_then(|(_, o)| o.as_array()) {
+            for pair in names.chunks_exact(2) {
+                if let Ok((_, Object::String(name, _))) = document.dereference(&pair[0]) {
+                    entries.push((name.clone(), &pair[1]));
+                }
+            }
+        }
+        if let Ok(kids) = node.get(b"Kids").and_then(|o| document.dereference(o)).and_then(|(_, o)| o.as_array()) {
+            for kid in kids {
+                if let Ok((_, Object::Dictionary(kid))) = document.dereference(kid) {
+                    Self::collect_name_tree(document, kid, entries, depth + 1);
+                }
+            }
+        }
+    }
+
+    /// 页面显示区域: CropBox 优先, 其次 MediaBox, 缺省为 Letter
+    fn parse_page_box(document: &Document, page_id: ObjectId) -> ([f32; 4], i64) {
+        let rect = Self::inherited_page_attr(document, page_id, b"CropBox")
+            .or_else(|| Self::inherited_page_attr(document, page_id, b"MediaBox"))
+            .and_then(|rect| match rect.as_array() {
+                Ok(rect) => {
+                    let values: Vec<f32> = rect.iter().filter_map(|v| v.as_float().ok()).collect();
+                    match values[..] {
+                        [x0, y0, x1, y1] if x0 != x1 && y0 != y1 => Some([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)]),
+                        _ => None,
+                    }
+                }
+                Err(_) => None,
+            })
+            .unwrap_or([0.0, 0.0, 612.0, 792.0]);
+        let rotate = Self::inherited_page_attr(document, page_id, b"Rotate")
+            .and_then(|rotate| rotate.as_i64().ok())
+            .unwrap_or(0);
+        (rect, rotate)
+    }
+
+    /// 页面显示大小, 旋转 90/270 度时宽高互换
+    fn page_size([x0, y0, x1, y1]: [f32; 4], rotate: i64) -> (f32, f32) {
+        let (width, height) = (x1 - x0, y1 - y0);
+        if rotate.rem_euclid(180) == 90 {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// 页面属性, 可从父节点继承
+    fn inherited_page_attr<'a>(document: &'a Document, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+        let mut node = document.get_dictionary(page_id).ok();
+        while let Some(dict) = node {
+            if let Ok((_, value)) = dict.get(key).and_then(|o| document.dereference(o)) {
+                return Some(value);
+            }
+            node = dict.get(b"Parent").and_then(Object::as_reference).and_then(|id| document.get_dictionary(id)).ok();
+        }
+        None
+    }
+
 
     pub fn find_book_mark(&self, index: &BookMarkIndex) -> Option<BookMarkType> {
         let bms: &Vec<BookMarkType> = &self.book_marks;
@@ -180,10 +508,18 @@ impl PdfHandler {
 
     /// 获取书签目录
     fn parse_book_marks(&mut self) {
-        let mut map = BTreeMap::new();
+        let first = self.document.catalog().ok()
+            .and_then(|catalog| catalog.get(b"Outlines").ok())
+            .and_then(|outlines| self.document.dereference(outlines).ok())
+            .and_then(|(_, outlines)| outlines.as_dict().ok())
+            .and_then(|outlines| outlines.get(b"First").ok());
         let mut book_marks = vec![];
-        if let Some(outlines) = self.document.get_outlines(None, None, &mut map).unwrap() {
-            self.parse_outlines(&outlines, &mut book_marks, 0);
+        let mut skipped = vec![];
+        if let Some(first) = first {
+            self.parse_outlines(first, &mut book_marks, 0, &mut BTreeSet::new(), &mut skipped);
+        }
+        if !skipped.is_empty() {
+            self.warnings.push(format!("跳过 {} 个损坏的书签: {}", skipped.len(), skipped.join(", ")));
         }
         let mut book_marks_pages = vec![];
         self.map_book_marks_pages(&book_marks, &mut book_marks_pages);
@@ -191,47 +527,57 @@ impl PdfHandler {
         self.book_marks_pages = book_marks_pages;
     }
 
-    pub fn parse_outlines(&self, outlines: &Vec<Outline>, book_marks: &mut Vec<BookMarkType>, hierarchy: u32) {
-        for outline in outlines.iter() {
-            let mut book_mark = BookMark::default();
-            match outline {
-                Outline::Destination(dest) => {
-                    let title = dest.title().unwrap();
-                    let title = if let Object::String(bytes, _) = title {
-                        decode_str_to_utf8(bytes).unwrap_or("unknown".to_owned())
-                    } else {
-                        "unknown".to_owned()
-                    };
-                    let page = dest.page().unwrap();
-                    let mut num = 0;
-                    if let Object::Reference(id) = page {
-                        num = *self.page_map.get(id).unwrap();
-                    }
-                    book_mark = book_mark
-                        .name(title)
-                        .hierarchy(hierarchy)
-                        .num(num)
-                        .show(hierarchy == 0);
-                }
-                Outline::SubOutlines(os) => {
-                    let mut sub_marks = vec![];
-                    self.parse_outlines(os, &mut sub_marks, hierarchy + 1);
-                    if let Some(last) = book_marks.last_mut() {
-                        for sub in sub_marks.iter() {
-                            sub.borrow_mut().parent = Some(last.clone());
-                        }
-                        last.borrow_mut().sub = sub_marks;
-                    }
-                    continue;
+    /// 从 `first` 起沿 /Next 解析同级大纲项, /First 为子目录, 记录访问过的对象以防循环
+    /// 无法定位又没有子目录的书签跳过, 标题记入 `skipped`
+    pub fn parse_outlines(&self, first: &Object, book_marks: &mut Vec<BookMarkType>, hierarchy: u32,
+                          visited: &mut BTreeSet<ObjectId>, skipped: &mut Vec<String>) {
+        let mut next = Some(first);
+        while let Some(item) = next.take() {
+            if let Object::Reference(id) = item {
+                if !visited.insert(*id) {
+                    skipped.push(String::from("(循环引用)"));
+                    break;
                 }
             }
-            book_marks.push(Rc::new(RefCell::new(book_mark)));
+            let Ok((_, Object::Dictionary(item))) = self.document.dereference(item) else {
+                skipped.push(String::from("(无效对象)"));
+                break;
+            };
+            let title = match item.get(b"Title").and_then(|title| self.document.dereference(title)) {
+                Ok((_, Object::String(bytes, _))) => decode_text_string(bytes),
+                _ => "unknown".to_owned(),
+            };
+            // /Dest 优先, 其次 /A GoTo 动作
+            let num = match item.get(b"Dest") {
+                Ok(dest) => self.resolve_dest(dest),
+                Err(_) => item.get(b"A").ok().and_then(|action| self.resolve_action(action)),
+            };
+            next = item.get(b"Next").ok();
+            let mut sub_marks = vec![];
+            if let Ok(child) = item.get(b"First") {
+                self.parse_outlines(child, &mut sub_marks, hierarchy + 1, visited, skipped);
+            }
+            // 没有目标的章节标题指向第一个子书签
+            let Some(num) = num.or_else(|| sub_marks.first().map(|sub| sub.borrow().num)) else {
+                skipped.push(title);
+                continue;
+            };
+            let book_mark = BookMark::default()
+                .name(title)
+                .hierarchy(hierarchy)
+                .num(num)
+                .show(hierarchy == 0);
+            let book_mark = Rc::new(RefCell::new(book_mark));
+            for sub in sub_marks.iter() {
+                sub.borrow_mut().parent = Some(book_mark.clone());
+            }
+            book_mark.borrow_mut().sub = sub_marks;
+            book_marks.push(book_mark);
         }
     }
 
-    pub fn map_book_marks_pages(&self, book_marks: &Vec<BookMarkType>, book_marks_pages: &mut Vec<BookMarkType>) {
-        for i in 0..book_marks.len() {
-            let marks = &book_marks[i];
+    pub fn map_book_marks_pages(&self, book_marks: &[BookMarkType], book_marks_pages: &mut Vec<BookMarkType>) {
+        for marks in book_marks.iter() {
             {
                 let marks = marks.borrow();
                 if !marks.sub.is_empty() {
@@ -261,12 +607,10 @@ impl PdfHandler {
                     right = mid - 1;
                 } else {
                     last_left = left as i32;
-                    left = left + 1;
+                    left += 1;
                 }
             }
-            if last_left == -1 {
-                self.book_marks_pages[left].clone()
-            } else if page_num > self.book_marks_pages[left].borrow().num {
+            if last_left == -1 || page_num > self.book_marks_pages[left].borrow().num {
                 self.book_marks_pages[left].clone()
             } else {
                 self.book_marks_pages[last_left as usize].clone()
@@ -298,6 +642,42 @@ impl PdfHandler {
     }
 }
 
+/// 解密文档. lopdf 只解密流, 间接字符串和 /Info, 字典与数组里的字符串 (书签标题, 命名目标等) 在这里解密
+pub fn decrypt_document(document: &mut Document, password: &str) -> lopdf::Result<()> {
+    let key = encryption::get_encryption_key(document, password, true)?;
+    let skipped = [b"Encrypt".as_slice(), b"Info"].map(|key| document.trailer.get(key).and_then(Object::as_reference).ok());
+    document.decrypt(password)?;
+    for (&id, object) in document.objects.iter_mut() {
+        if skipped.contains(&Some(id)) {
+            continue;
+        }
+        // 间接字符串本身 lopdf 已解密
+        match object {
+            Object::Dictionary(dict) => dict.iter_mut().for_each(|(_, value)| decrypt_nested_strings(&key, id, value)),
+            Object::Array(items) => items.iter_mut().for_each(|item| decrypt_nested_strings(&key, id, item)),
+            Object::Stream(stream) => stream.dict.iter_mut().for_each(|(_, value)| decrypt_nested_strings(&key, id, value)),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 用所在间接对象 `id` 的密钥解密字符串
+fn decrypt_nested_strings(key: &[u8], id: ObjectId, object: &mut Object) {
+    match object {
+        Object::String(_, _) => {
+            if let Ok(content) = encryption::decrypt_object(key, id, object) {
+                if let Object::String(bytes, _) = object {
+                    *bytes = content;
+                }
+            }
+        }
+        Object::Dictionary(dict) => dict.iter_mut().for_each(|(_, value)| decrypt_nested_strings(key, id, value)),
+        Object::Array(items) => items.iter_mut().for_each(|item| decrypt_nested_strings(key, id, item)),
+        _ => {}
+    }
+}
+
 impl PdfSize {
     pub fn new(width: i32, height: i32, x: u16, y: u16) -> Self {
         Self {
@@ -305,6 +685,8 @@ impl PdfSize {
             height,
             x,
             y,
+            zoom: ZoomMode::default(),
+            columns: 1,
         }
     }
     pub fn width(&self) -> i32 {
@@ -323,19 +705,112 @@ impl PdfSize {
         self.y
     }
 
-    pub(crate) fn increment(&mut self) {
-        self.width = (self.width as f32 * 1.1) as i32;
-        self.height = (self.height as f32 * 1.1) as i32;
+    pub fn zoom(&self) -> ZoomMode {
+        self.zoom
     }
 
-    pub(crate) fn decrement(&mut self) {
-        self.width = (self.width as f32 * 0.9) as i32;
-        self.height = (self.height as f32 * 0.9) as i32;
+    pub(crate) fn set_zoom(&mut self, zoom: ZoomMode) {
+        self.zoom = zoom;
     }
 
-    pub(crate) fn update(&mut self, rect: &Rect) {
+    /// 双页显示时每页只占一半宽度
+    pub(crate) fn set_columns(&mut self, columns: u32) {
+        self.columns = columns.max(1);
+    }
+
+    /// 在当前页的实际缩放比例上放大 10%
+    pub(crate) fn increment(&mut self, page_size: (f32, f32)) {
+        let percent = self.percent(page_size) + 10;
+        self.zoom = ZoomMode::Fixed(percent.min(ZoomMode::MAX_PERCENT));
+    }
+
+    /// 在当前页的实际缩放比例上缩小 10%
+    pub(crate) fn decrement(&mut self, page_size: (f32, f32)) {
+        let percent = self.percent(page_size).saturating_sub(10);
+        self.zoom = ZoomMode::Fixed(percent.max(ZoomMode::MIN_PERCENT));
+    }
+
+    /// 根据预览区位置和终端字符像素大小更新
+    pub(crate) fn update(&mut self, rect: &Rect, (cell_width, cell_height): (u16, u16)) {
         self.x = rect.x;
         self.y = rect.y;
+        self.width = rect.width as i32 * cell_width as i32;
+        self.height = rect.height as i32 * cell_height as i32;
+    }
+
+    /// 每 pt 对应的像素数
+    fn scale(&self, (page_width, page_height): (f32, f32)) -> f32 {
+        let width = (self.width.max(1) as u32 / self.columns).max(1) as f32;
+        let height = self.height.max(1) as f32;
+        match self.zoom {
+            ZoomMode::FitPage => (width / page_width).min(height / page_height),
+            ZoomMode::FitWidth => width / page_width,
+            ZoomMode::FitHeight => height / page_height,
+            ZoomMode::Fixed(percent) => percent as f32 / 100.0 * 96.0 / 72.0,
+        }
+    }
+
+    /// 当前页实际的缩放百分比
+    pub fn percent(&self, page_size: (f32, f32)) -> u32 {
+        (self.scale(page_size) * 72.0 / 96.0 * 100.0).round() as u32
+    }
+
+    /// 页面按缩放模式渲染的像素大小
+    pub fn fit(&self, page_size: (f32, f32)) -> (u32, u32) {
+        let scale = self.scale(page_size);
+        (((page_size.0 * scale) as u32).max(1), ((page_size.1 * scale) as u32).max(1))
+    }
+
+    /// 渲染结果只取决于缩放模式和它用到的预览区大小
+    pub fn cache_key(&self) -> String {
+        let width = self.width / self.columns as i32;
+        match self.zoom {
+            ZoomMode::FitPage => format!("page-{}x{}", width, self.height),
+            ZoomMode::FitWidth => format!("width-{}", width),
+            ZoomMode::FitHeight => format!("height-{}", self.height),
+            ZoomMode::Fixed(percent) => format!("zoom-{percent}"),
+        }
+    }
+}
+
+impl ZoomMode {
+    const MIN_PERCENT: u32 = 10;
+    const MAX_PERCENT: u32 = 800;
+
+    /// 整页 => 页宽 => 页高 => 整页
+    pub fn next(self) -> Self {
+        match self {
+            ZoomMode::FitPage => ZoomMode::FitWidth,
+            ZoomMode::FitWidth => ZoomMode::FitHeight,
+            ZoomMode::FitHeight | ZoomMode::Fixed(_) => ZoomMode::FitPage,
+        }
+    }
+}
+
+impl Display for ZoomMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ZoomMode::FitPage => write!(f, "fit-page"),
+            ZoomMode::FitWidth => write!(f, "fit-width"),
+            ZoomMode::FitHeight => write!(f, "fit-height"),
+            ZoomMode::Fixed(percent) => write!(f, "{percent}%"),
+        }
+    }
+}
+
+impl FromStr for ZoomMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fit-page" => Ok(ZoomMode::FitPage),
+            "fit-width" => Ok(ZoomMode::FitWidth),
+            "fit-height" => Ok(ZoomMode::FitHeight),
+            _ => match s.strip_suffix('%').map(str::parse::<u32>) {
+                Some(Ok(percent)) => Ok(ZoomMode::Fixed(percent.clamp(ZoomMode::MIN_PERCENT, ZoomMode::MAX_PERCENT))),
+                _ => bail!("unknown zoom mode {s}"),
+            }
+        }
     }
 }
 
@@ -375,6 +850,10 @@ impl BookMarkIndex {
         self.inner.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
     // pub fn next(mut self, pdf_handler: &PdfHandler) -> Option<&BookMark> {
     //     let mut inner: &mut [usize] = &mut self.inner;
     //     loop {
@@ -393,4 +872,256 @@ impl BookMarkIndex {
     //
     //     // if inner.len() > 1 {}
     // }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use lopdf::{dictionary, encryption, Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+    use lopdf::encryption::DecryptionError;
+    use super::{decrypt_document, PageLink, PdfHandler};
+
+    /// 有 `page_count` 页的文档及各页对象 id
+    fn new_document(page_count: usize) -> (Document, Vec<ObjectId>) {
+        let mut document = Document::with_version("1.5");
+        let pages_id = document.new_object_id();
+        let page_ids: Vec<ObjectId> = (0..page_count).map(|_| document.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+        })).collect();
+        document.objects.insert(pages_id, Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => page_ids.iter().map(|&id| Object::Reference(id)).collect::<Vec<_>>(),
+            "Count" => page_count as i64,
+        }));
+        let catalog_id = document.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        document.trailer.set("Root", catalog_id);
+        (document, page_ids)
+    }
+
+    fn link(rect: [i64; 4], key: &str, target: Object) -> Dictionary {
+        dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => rect.map(Object::from).to_vec(),
+            key => target,
+        }
+    }
+
+    #[test]
+    fn page_links() {
+        let (mut document, pages) = new_document(3);
+        let fit = |page: Object| Object::Array(vec![page, "Fit".into()]);
+        let indirect = document.add_object(link([20, 400, 80, 420], "Dest", fit(pages[0].into())));
+        let annots: Vec<Object> = vec![
+            // 反向的 Rect 规整为左下, 右上
+            link([300, 700, 100, 650], "Dest", fit(pages[2].into())).into(),
+            // 从 0 开始的页下标
+            link([20, 680, 80, 700], "Dest", fit(1.into())).into(),
+            link([20, 600, 80, 620], "Dest", fit(7.into())).into(),
+            link([20, 500, 80, 520], "A", dictionary! { "S" => "URI", "URI" => Object::string_literal("https://example.com") }.into()).into(),
+            dictionary! { "Type" => "Annot", "Subtype" => "Text", "Rect" => vec![0.into(), 0.into(), 1.into(), 1.into()] }.into(),
+            indirect.into(),
+        ];
+        document.get_dictionary_mut(pages[0]).unwrap().set("Annots", annots);
+        let pdf_handler = PdfHandler::from_document("links.pdf", document, None).unwrap();
+        assert_eq!(pdf_handler.get_page_links(1), vec![
+            PageLink { rect: [20.0, 680.0, 80.0, 700.0], target: 2 },
+            PageLink { rect: [100.0, 650.0, 300.0, 700.0], target: 3 },
+            PageLink { rect: [20.0, 400.0, 80.0, 420.0], target: 1 },
+        ]);
+        assert!(pdf_handler.get_page_links(2).is_empty());
+        assert!(pdf_handler.get_page_links(4).is_empty());
+    }
+
+    #[test]
+    fn named_dests_and_outlines() {
+        let (mut document, pages) = new_document(4);
+        let fit = |page: ObjectId| Object::Array(vec![page.into(), "Fit".into()]);
+        let chap2 = document.add_object(fit(pages[2]));
+        let leaf = document.add_object(dictionary! {
+            "Names" => vec![Object::string_literal("chap2"), chap2.into()],
+        });
+        let middle = document.add_object(dictionary! { "Kids" => vec![leaf.into()] });
+        let first = document.add_object(dictionary! {
+            "Limits" => vec![Object::string_literal("chap1"), Object::string_literal("intro")],
+            "Names" => vec![
+                Object::string_literal("intro"), fit(pages[0]),
+                Object::string_literal("chap1"), dictionary! { "D" => fit(pages[1]) }.into(),
+            ],
+        });
+        let tree = document.add_object(dictionary! { "Kids" => vec![first.into(), middle.into()] });
+
+        let [intro, part, chap, old, broken] = [(); 5].map(|_| document.new_object_id());
+        let outline = |title: &str, next: Option<ObjectId>| {
+            let mut item = dictionary! { "Title" => Object::string_literal(title) };
+            if let Some(next) = next {
+                item.set("Next", next);
+            }
+            item
+        };
+        let mut item = outline("Intro", Some(part));
+        item.set("A", dictionary! { "S" => "GoTo", "D" => Object::string_literal("intro") });
+        document.objects.insert(intro, item.into());
+        // 没有目标, 指向第一个子书签
+        let mut item = outline("Part", None);
+        item.set("First", chap);
+        document.objects.insert(part, item.into());
+        let mut item = outline("Chapter 2", Some(old));
+        item.set("Dest", Object::string_literal("chap2"));
+        document.objects.insert(chap, item.into());
+        let mut item = outline("Old", Some(broken));
+        item.set("Dest", Object::Name(b"old".to_vec()));
+        document.objects.insert(old, item.into());
+        let mut item = outline("Broken", None);
+        item.set("Dest", Object::string_literal("missing"));
+        document.objects.insert(broken, item.into());
+        let outlines = document.add_object(dictionary! { "First" => intro, "Last" => part });
+
+        let catalog = document.catalog_mut().unwrap();
+        catalog.set("Dests", dictionary! { "old" => fit(pages[3]) });
+        catalog.set("Names", dictionary! { "Dests" => tree });
+        catalog.set("Outlines", outlines);
+        let pdf_handler = PdfHandler::from_document("dests.pdf", document, None).unwrap();
+
+        let dests: Vec<(&[u8], u32)> = pdf_handler.named_dests.iter().map(|(name, &num)| (name.as_slice(), num)).collect();
+        assert_eq!(dests, vec![(b"chap1".as_slice(), 2), (b"chap2", 3), (b"intro", 1), (b"old", 4)]);
+
+        let book_marks: Vec<(String, u32, u32)> = pdf_handler.get_book_marks().iter()
+            .flat_map(|book_mark| {
+                let book_mark = book_mark.borrow();
+                let subs: Vec<_> = book_mark.get_sub().iter().map(|sub| sub.borrow().clone()).collect();
+                [book_mark.clone()].into_iter().chain(subs)
+            })
+            .map(|book_mark| (book_mark.get_name().to_string(), book_mark.get_num(), book_mark.get_hierarchy()))
+            .collect();
+        assert_eq!(book_marks, vec![
+            (String::from("Intro"), 1, 0),
+            (String::from("Part"), 3, 0),
+            (String::from("Chapter 2"), 3, 1),
+            (String::from("Old"), 4, 1),
+        ]);
+        assert_eq!(*pdf_handler.get_warnings(), ["跳过 1 个损坏的书签: Broken"]);
+    }
+
+    /// 两页, 书签标题, 命名目标, /Info 与内容流都含字符串的文档
+    fn document_with_strings() -> Document {
+        let (mut document, pages) = new_document(2);
+        let content = document.add_object(Stream::new(dictionary! {}, b"BT (Hello) Tj ET".to_vec()));
+        document.get_dictionary_mut(pages[0]).unwrap().set("Contents", content);
+        let tree = document.add_object(dictionary! {
+            "Names" => vec![Object::string_literal("intro"), vec![pages[1].into(), "Fit".into()].into()],
+        });
+        let item = document.new_object_id();
+        let outlines = document.add_object(dictionary! { "First" => item, "Last" => item });
+        document.objects.insert(item, dictionary! {
+            "Title" => Object::string_literal("Chapter"),
+            "Parent" => outlines,
+            "Dest" => Object::string_literal("intro"),
+        }.into());
+        let catalog = document.catalog_mut().unwrap();
+        catalog.set("Names", dictionary! { "Dests" => tree });
+        catalog.set("Outlines", outlines);
+        let info = document.add_object(dictionary! { "Title" => Object::string_literal("Secret Title") });
+        document.trailer.set("Info", info);
+        let id = Object::String((0..16).collect(), StringFormat::Hexadecimal);
+        document.trailer.set("ID", vec![id.clone(), id]);
+        document
+    }
+
+    /// RC4 40 位 (V1 R2) 加密, 用户密码 secret, 所有者密码 owner
+    fn encrypt_rc4(document: &mut Document) {
+        let hex = |hex: &str| Object::String(
+            (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect(),
+            StringFormat::Hexadecimal,
+        );
+        let encrypt = document.add_object(dictionary! {
+            "Filter" => "Standard",
+            "V" => 1,
+            "R" => 2,
+            "Length" => 40,
+            "P" => -4,
+            "O" => hex("92fe0f4454ad4c9644693f33c07cb54f587dce1e2682fe9ecea6107a1ef630dd"),
+            "U" => hex("a2d98dd93474875ec95c60445c51fe1df35ffaac7da1baca629058b1c08a8e39"),
+        });
+        document.trailer.set("Encrypt", encrypt);
+        let key = encryption::get_encryption_key(document, "secret", true).unwrap();
+        for (&id, object) in document.objects.iter_mut() {
+            if id != encrypt {
+                encrypt_strings(&key, id, object);
+            }
+        }
+    }
+
+    /// RC4 加密与解密是同一运算
+    fn encrypt_strings(key: &[u8], id: ObjectId, object: &mut Object) {
+        match object {
+            Object::String(bytes, _) => *bytes = encryption::decrypt_object(key, id, &Object::String(bytes.clone(), StringFormat::Literal)).unwrap(),
+            Object::Stream(stream) => {
+                let content = encryption::decrypt_object(key, id, &Object::Stream(stream.clone())).unwrap();
+                stream.set_content(content);
+            }
+            Object::Array(items) => items.iter_mut().for_each(|item| encrypt_strings(key, id, item)),
+            Object::Dictionary(dict) => dict.iter_mut().for_each(|(_, value)| encrypt_strings(key, id, value)),
+            _ => {}
+        }
+    }
+
+    fn outline_title(document: &Document) -> Vec<u8> {
+        let outlines = document.catalog().unwrap().get(b"Outlines").unwrap().as_reference().unwrap();
+        let item = document.get_dictionary(outlines).unwrap().get(b"First").unwrap().as_reference().unwrap();
+        document.get_dictionary(item).unwrap().get(b"Title").unwrap().as_str().unwrap().to_vec()
+    }
+
+    #[test]
+    fn decrypt_rc4_nested_strings() {
+        let mut document = document_with_strings();
+        encrypt_rc4(&mut document);
+        assert_ne!(outline_title(&document), b"Chapter");
+        assert!(matches!(decrypt_document(&mut document, "wrong"),
+            Err(lopdf::Error::Decryption(DecryptionError::IncorrectPassword))));
+
+        decrypt_document(&mut document, "secret").unwrap();
+        assert!(!document.is_encrypted());
+        assert_eq!(outline_title(&document), b"Chapter");
+        let names = document.catalog().unwrap().get(b"Names").unwrap().as_dict().unwrap()
+            .get(b"Dests").unwrap().as_reference().unwrap();
+        let names = document.get_dictionary(names).unwrap().get(b"Names").unwrap().as_array().unwrap();
+        assert_eq!(names[0].as_str().unwrap(), b"intro");
+        let info = document.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        assert_eq!(document.get_dictionary(info).unwrap().get(b"Title").unwrap().as_str().unwrap(), b"Secret Title");
+        let page = document.page_iter().next().unwrap();
+        assert_eq!(document.get_page_content(page).unwrap(), b"BT (Hello) Tj ET");
+    }
+
+    #[test]
+    fn locked_until_unlocked() {
+        let mut document = document_with_strings();
+        encrypt_rc4(&mut document);
+        let mut pdf_handler = PdfHandler::from_document("secret.pdf", document, Some("wrong")).unwrap();
+        assert!(pdf_handler.is_locked());
+        assert!(pdf_handler.get_book_marks().is_empty());
+        assert!(!pdf_handler.unlock("still wrong").unwrap());
+        assert!(pdf_handler.unlock("secret").unwrap());
+        assert!(!pdf_handler.is_locked());
+        assert_eq!(pdf_handler.get_password(), Some("secret"));
+        assert_eq!(pdf_handler.get_title(), "Secret Title");
+        let book_mark = pdf_handler.get_book_marks()[0].borrow().clone();
+        assert_eq!((book_mark.get_name(), book_mark.get_num()), ("Chapter", 2));
+    }
+
+    #[test]
+    fn unsupported_encryption_renders_only() {
+        let mut document = document_with_strings();
+        let encrypt = document.add_object(dictionary! { "Filter" => "Standard", "V" => 4, "R" => 4, "Length" => 128 });
+        document.trailer.set("Encrypt", encrypt);
+        let pdf_handler = PdfHandler::from_document("aes.pdf", document, Some("pw")).unwrap();
+        assert!(!pdf_handler.is_locked());
+        assert!(pdf_handler.is_render_only());
+        assert_eq!(pdf_handler.get_password(), Some("pw"));
+        assert_eq!(pdf_handler.get_page_nums(), 2);
+        assert!(pdf_handler.get_book_marks().is_empty());
+        assert!(pdf_handler.get_page_text(1).is_none());
+        assert_eq!(pdf_handler.get_page_texts().count(), 0);
+        assert_eq!(pdf_handler.get_warnings().len(), 1);
+    }
+}
@@ -0,0 +1,252 @@
+use std::env;
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+use anyhow::{anyhow, bail};
+use clap::ValueEnum;
+
+/// Renders a single pdf page to an image with an external tool.
+pub trait Rasterizer: Debug + Send + Sync {
+    /// Executable looked up in `PATH`.
+    fn program(&self) -> &'static str;
+
+    /// Package to suggest when the executable is missing.
+    fn package(&self) -> &'static str;
+
+    /// Arguments writing page `page_id` of `pdf_path` to stdout,
+    /// scaled to `(width, height)` pixels, opening it with `password` if encrypted.
+    fn args(&self, pdf_path: &str, page_id: u32, size: (u32, u32), password: Option<&str>) -> Vec<String>;
+
+    /// Fail with a readable message if the tool isn't installed.
+    fn check(&self) -> anyhow::Result<PathBuf> {
+        find_in_path(self.program()).ok_or_else(|| anyhow!(
+            "`{}` not found in PATH, install {} or choose another --rasterizer",
+            self.program(), self.package()
+        ))
+    }
+
+    /// Render the page at `(width, height)` pixels, returning the encoded image.
+    /// Looks the tool up in `PATH` each time unless built through [`RasterizerKind::build`].
+    fn render_page(&self, pdf_path: &str, page_id: u32, size: (u32, u32), password: Option<&str>) -> anyhow::Result<Vec<u8>> {
+        let program = self.check()?;
+        let output = Command::new(program).args(self.args(pdf_path, page_id, size, password)).output()?;
+        if !output.status.success() || output.stdout.is_empty() {
+            bail!("{} failed to render page {page_id}: {}",
+                self.program(), String::from_utf8_lossy(&output.stderr).trim())
+        }
+        Ok(output.stdout)
+    }
+}
+
+/// Rasterizers selectable with `--rasterizer`, in auto-detection order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RasterizerKind {
+    /// poppler `pdftoppm`
+    Pdftoppm,
+    /// poppler `pdftocairo`
+    Pdftocairo,
+    /// MuPDF `mutool draw`
+    Mutool,
+    /// Ghostscript `gs`
+    Ghostscript,
+}
+
+impl RasterizerKind {
+    fn backend(self) -> Box<dyn Rasterizer> {
+        match self {
+            RasterizerKind::Pdftoppm => Box::new(Pdftoppm),
+            RasterizerKind::Pdftocairo => Box::new(Pdftocairo),
+            RasterizerKind::Mutool => Box::new(Mutool),
+            RasterizerKind::Ghostscript => Box::new(Ghostscript),
+        }
+    }
+
+    /// The rasterizer with its tool looked up in `PATH` once, not for every page.
+    pub fn build(self) -> anyhow::Result<Arc<dyn Rasterizer>> {
+        let backend = self.backend();
+        let path = backend.check()?;
+        Ok(Arc::new(Resolved { backend, path }))
+    }
+
+    /// First rasterizer whose tool is installed.
+    pub fn detect() -> Option<Self> {
+        Self::value_variants().iter().copied().find(|kind| kind.backend().check().is_ok())
+    }
+}
+
+/// A backend and the executable found for it.
+#[derive(Debug)]
+struct Resolved {
+    backend: Box<dyn Rasterizer>,
+    path: PathBuf,
+}
+
+impl Rasterizer for Resolved {
+    fn program(&self) -> &'static str {
+        self.backend.program()
+    }
+
+    fn package(&self) -> &'static str {
+        self.backend.package()
+    }
+
+    fn args(&self, pdf_path: &str, page_id: u32, size: (u32, u32), password: Option<&str>) -> Vec<String> {
+        self.backend.args(pdf_path, page_id, size, password)
+    }
+
+    fn check(&self) -> anyhow::Result<PathBuf> {
+        Ok(self.path.clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct Pdftoppm;
+
+impl Rasterizer for Pdftoppm {
+    fn program(&self) -> &'static str {
+        "pdftoppm"
+    }
+
+    fn package(&self) -> &'static str {
+        "poppler"
+    }
+
+    fn args(&self, pdf_path: &str, page_id: u32, (width, height): (u32, u32), password: Option<&str>) -> Vec<String> {
+        let (page, width, height) = (page_id.to_string(), width.to_string(), height.to_string());
+        // without an output root the image goes to stdout
+        let mut args = poppler_password_args(password);
+        args.extend(["-jpeg", "-jpegopt", "quality=70", "-singlefile", "-f", &page, "-l", &page,
+            "-scale-to-x", &width, "-scale-to-y", &height, pdf_path]
+            .map(String::from));
+        args
+    }
+}
+
+#[derive(Debug)]
+pub struct Pdftocairo;
+
+impl Rasterizer for Pdftocairo {
+    fn program(&self) -> &'static str {
+        "pdftocairo"
+    }
+
+    fn package(&self) -> &'static str {
+        "poppler"
+    }
+
+    fn args(&self, pdf_path: &str, page_id: u32, (width, height): (u32, u32), password: Option<&str>) -> Vec<String> {
+        let (page, width, height) = (page_id.to_string(), width.to_string(), height.to_string());
+        let mut args = poppler_password_args(password);
+        args.extend(["-jpeg", "-singlefile", "-f", &page, "-l", &page,
+            "-scale-to-x", &width, "-scale-to-y", &height, pdf_path, "-"]
+            .map(String::from));
+        args
+    }
+}
+
+#[derive(Debug)]
+pub struct Mutool;
+
+impl Rasterizer for Mutool {
+    fn program(&self) -> &'static str {
+        "mutool"
+    }
+
+    fn package(&self) -> &'static str {
+        "mupdf-tools"
+    }
+
+    fn args(&self, pdf_path: &str, page_id: u32, (width, height): (u32, u32), password: Option<&str>) -> Vec<String> {
+        let (page, width, height) = (page_id.to_string(), width.to_string(), height.to_string());
+        let mut args = vec![String::from("draw")];
+        if let Some(password) = password {
+            args.extend([String::from("-p"), password.to_string()]);
+        }
+        args.extend(["-q", "-F", "png", "-w", &width, "-h", &height, "-o", "-", pdf_path, &page]
+            .map(String::from));
+        args
+    }
+}
+
+#[derive(Debug)]
+pub struct Ghostscript;
+
+impl Rasterizer for Ghostscript {
+    fn program(&self) -> &'static str {
+        "gs"
+    }
+
+    fn package(&self) -> &'static str {
+        "ghostscript"
+    }
+
+    fn args(&self, pdf_path: &str, page_id: u32, (width, height): (u32, u32), password: Option<&str>) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "-q".into(), "-dSAFER".into(), "-dBATCH".into(), "-dNOPAUSE".into(),
+            "-sDEVICE=jpeg".into(), "-dJPEGQ=70".into(),
+            format!("-g{width}x{height}"), "-dPDFFitPage".into(),
+            format!("-dFirstPage={page_id}"), format!("-dLastPage={page_id}"),
+            "-sOutputFile=-".into(),
+        ];
+        if let Some(password) = password {
+            args.push(format!("-sPDFPassword={password}"));
+        }
+        args.push(pdf_path.into());
+        args
+    }
+}
+
+/// poppler tries the owner password first and then the user password, the user doesn't know which one they have
+fn poppler_password_args(password: Option<&str>) -> Vec<String> {
+    match password {
+        Some(password) => ["-opw", password, "-upw", password].map(String::from).to_vec(),
+        None => vec![],
+    }
+}
+
+pub fn find_in_path(program: &str) -> Option<PathBuf> {
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths)
+        .map(|dir| dir.join(program))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ghostscript, Mutool, Pdftocairo, Pdftoppm, Rasterizer};
+
+    fn args(rasterizer: &dyn Rasterizer, password: Option<&str>) -> String {
+        rasterizer.args("a b.pdf", 3, (600, 800), password).join(" ")
+    }
+
+    #[test]
+    fn pdftoppm_args() {
+        assert_eq!(args(&Pdftoppm, None),
+                   "-jpeg -jpegopt quality=70 -singlefile -f 3 -l 3 -scale-to-x 600 -scale-to-y 800 a b.pdf");
+        assert_eq!(args(&Pdftoppm, Some("pw")),
+                   "-opw pw -upw pw -jpeg -jpegopt quality=70 -singlefile -f 3 -l 3 -scale-to-x 600 -scale-to-y 800 a b.pdf");
+    }
+
+    #[test]
+    fn pdftocairo_args() {
+        assert_eq!(args(&Pdftocairo, None),
+                   "-jpeg -singlefile -f 3 -l 3 -scale-to-x 600 -scale-to-y 800 a b.pdf -");
+        assert_eq!(args(&Pdftocairo, Some("pw")),
+                   "-opw pw -upw pw -jpeg -singlefile -f 3 -l 3 -scale-to-x 600 -scale-to-y 800 a b.pdf -");
+    }
+
+    #[test]
+    fn mutool_args() {
+        assert_eq!(args(&Mutool, None), "draw -q -F png -w 600 -h 800 -o - a b.pdf 3");
+        assert_eq!(args(&Mutool, Some("pw")), "draw -p pw -q -F png -w 600 -h 800 -o - a b.pdf 3");
+    }
+
+    #[test]
+    fn ghostscript_args() {
+        let common = "-q -dSAFER -dBATCH -dNOPAUSE -sDEVICE=jpeg -dJPEGQ=70 -g600x800 -dPDFFitPage \
+                      -dFirstPage=3 -dLastPage=3 -sOutputFile=-";
+        assert_eq!(args(&Ghostscript, None), format!("{common} a b.pdf"));
+        assert_eq!(args(&Ghostscript, Some("pw")), format!("{common} -sPDFPassword=pw a b.pdf"));
+    }
+}
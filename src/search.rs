@@ -0,0 +1,70 @@
+use regex::{Regex, RegexBuilder};
+
+/// Characters of context kept on each side of a hit
+const SNIPPET_CONTEXT: usize = 20;
+
+/// One hit of a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// page number, starting from 1
+    pub page: u32,
+    /// byte range of the hit in the page text
+    pub range: (usize, usize),
+    /// text around the hit on a single line
+    pub snippet: String,
+    /// byte range of the hit in `snippet`
+    pub snippet_range: (usize, usize),
+}
+
+/// Case-insensitive matcher for `query`, taken literally unless `is_regex`.
+pub fn build_matcher(query: &str, is_regex: bool) -> anyhow::Result<Regex> {
+    let pattern = if is_regex { query.to_string() } else { regex::escape(query) };
+    Ok(RegexBuilder::new(&pattern).case_insensitive(true).build()?)
+}
+
+/// All hits of `matcher` in the text of `page`.
+pub fn find_matches(matcher: &Regex, page: u32, text: &str) -> Vec<SearchMatch> {
+    matcher.find_iter(text)
+        .filter(|m| !m.is_empty())
+        .map(|m| {
+            let (snippet, snippet_range) = snippet(text, m.start(), m.end());
+            SearchMatch { page, range: (m.start(), m.end()), snippet, snippet_range }
+        })
+        .collect()
+}
+
+fn snippet(text: &str, start: usize, end: usize) -> (String, (usize, usize)) {
+    let before: String = text[..start].chars().rev().take(SNIPPET_CONTEXT).collect::<Vec<_>>().into_iter().rev().collect();
+    let after: String = text[end..].chars().take(SNIPPET_CONTEXT).collect();
+    let flatten = |s: &str| s.replace(['\n', '\r', '\t'], " ");
+    let before = flatten(&before);
+    let hit = flatten(&text[start..end]);
+    let snippet = format!("{before}{hit}{}", flatten(&after));
+    (snippet, (before.len(), before.len() + hit.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_matcher, find_matches};
+
+    #[test]
+    fn literal_ignores_case() {
+        let matcher = build_matcher("a.b", false).unwrap();
+        let hits = find_matches(&matcher, 3, "axb A.B\nfoo");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].page, 3);
+        assert_eq!(hits[0].range, (4, 7));
+        assert_eq!(hits[0].snippet, "axb A.B foo");
+        assert_eq!(&hits[0].snippet[hits[0].snippet_range.0..hits[0].snippet_range.1], "A.B");
+    }
+
+    #[test]
+    fn regex_and_context() {
+        let matcher = build_matcher(r"page \d+", true).unwrap();
+        let text = format!("{}see Page 12 now", "字".repeat(30));
+        let hits = find_matches(&matcher, 1, &text);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].snippet, format!("{}see Page 12 now", "字".repeat(16)));
+        assert!(build_matcher("(", true).is_err());
+    }
+}
@@ -0,0 +1,561 @@
+use std::collections::{BTreeMap, HashMap};
+use lopdf::content::{Content, Operation};
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthChar;
+use crate::decode::{is_unicode_cmap, utf16_be, SimpleEncoding, ToUnicode};
+
+/// TJ 中小于该值(千分之一字号)的位移视为词间空格
+const TJ_SPACE_THRESHOLD: f32 = -150.0;
+/// 字体未给出宽度时的字宽(千分之一字号)
+const DEFAULT_GLYPH_WIDTH: f32 = 500.0;
+/// CID 字体未给出宽度时的字宽
+const DEFAULT_CID_WIDTH: f32 = 1000.0;
+/// 文本框相对基线的下沿和上沿(字号倍数)
+const DESCENT: f32 = -0.2;
+const ASCENT: f32 = 0.8;
+
+/// 仿射矩阵 [a b c d e f]
+type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// 一页的文本及各段文字在页面上的位置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PageText {
+    pub text: String,
+    pub spans: Vec<TextSpan>,
+}
+
+/// 一次文本绘制操作写出的文字
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextSpan {
+    /// 在页文本中的字节范围
+    pub range: (usize, usize),
+    /// 页面用户空间中的外框 [x0, y0, x1, y1]
+    pub bbox: [f32; 4],
+}
+
+/// 提取一页的文本, 按内容流顺序, 换行由文本定位操作推断
+pub fn extract_page_text(document: &Document, page_id: ObjectId) -> PageText {
+    let Ok(data) = document.get_page_content(page_id) else {
+        return PageText::default();
+    };
+    let Ok(content) = Content::decode(&data) else {
+        return PageText::default();
+    };
+    let fonts = document.get_page_fonts(page_id).into_iter()
+        .map(|(name, font)| (name, Font::load(document, font)))
+        .collect();
+    collect_text(&content.operations, &fonts)
+}
+
+/// 字体的解码方式与字宽
+#[derive(Debug, Clone)]
+struct Font {
+    // 简单字体的编码
+    encoding: SimpleEncoding,
+    to_unicode: Option<ToUnicode>,
+    // Type0 字体, 字符码为多字节 CID
+    is_type0: bool,
+    // Type0 字体的 CMap 为 UCS2/UTF16, 字符码即 UTF-16BE
+    unicode_cmap: bool,
+    first_char: i64,
+    widths: Vec<f32>,
+    cid_widths: HashMap<u32, f32>,
+    default_width: f32,
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self {
+            encoding: SimpleEncoding::new("StandardEncoding", &[]),
+            to_unicode: None,
+            is_type0: false,
+            unicode_cmap: false,
+            first_char: 0,
+            widths: vec![],
+            cid_widths: HashMap::new(),
+            default_width: DEFAULT_GLYPH_WIDTH,
+        }
+    }
+}
+
+impl Font {
+    fn load(document: &Document, font: &Dictionary) -> Self {
+        let is_type0 = font.get(b"Subtype").and_then(Object::as_name).is_ok_and(|subtype| subtype == b"Type0");
+        let to_unicode = font.get(b"ToUnicode").ok()
+            .and_then(|object| document.dereference(object).ok())
+            .and_then(|(_, object)| object.as_stream().ok())
+            .map(|stream| ToUnicode::parse(&stream.decompressed_content().unwrap_or_else(|_| stream.content.clone())));
+        let widths = font.get(b"Widths").ok()
+            .and_then(|object| document.dereference(object).ok())
+            .and_then(|(_, object)| object.as_array().ok())
+            .map(|widths| widths.iter().map(|w| number(w).unwrap_or(DEFAULT_GLYPH_WIDTH)).collect())
+            .unwrap_or_default();
+        let mut result = Self {
+            encoding: SimpleEncoding::from_font(document, font),
+            to_unicode,
+            is_type0,
+            unicode_cmap: is_type0 && is_unicode_cmap(font.get_font_encoding()),
+            first_char: font.get(b"FirstChar").and_then(Object::as_i64).unwrap_or(0),
+            widths,
+            ..Self::default()
+        };
+        if is_type0 {
+            result.load_cid_widths(document, font);
+        }
+        result
+    }
+
+    /// CID 字宽在子字体的 /DW 和 /W 中: `c [w1 w2 ...]` 或 `c_first c_last w`
+    fn load_cid_widths(&mut self, document: &Document, font: &Dictionary) {
+        self.default_width = DEFAULT_CID_WIDTH;
+        let descendant = font.get(b"DescendantFonts").ok()
+            .and_then(|object| document.dereference(object).ok())
+            .and_then(|(_, object)| object.as_array().ok())
+            .and_then(|fonts| fonts.first())
+            .and_then(|object| document.dereference(object).ok())
+            .and_then(|(_, object)| object.as_dict().ok());
+        let Some(descendant) = descendant else {
+            return;
+        };
+        if let Some(width) = descendant.get(b"DW").ok().and_then(number) {
+            self.default_width = width;
+        }
+        let Some(w) = descendant.get(b"W").ok()
+            .and_then(|object| document.dereference(object).ok())
+            .and_then(|(_, object)| object.as_array().ok()) else {
+            return;
+        };
+        let mut pos = 0;
+        while pos + 1 < w.len() {
+            let Some(first) = number(&w[pos]) else {
+                break;
+            };
+            match document.dereference(&w[pos + 1]).map(|(_, object)| object) {
+                Ok(Object::Array(widths)) => {
+                    for (offset, width) in widths.iter().enumerate() {
+                        self.cid_widths.insert(first as u32 + offset as u32, number(width).unwrap_or(self.default_width));
+                    }
+                    pos += 2;
+                }
+                Ok(last) => {
+                    let (Some(last), Some(width)) = (number(last), w.get(pos + 2).and_then(number)) else {
+                        break;
+                    };
+                    for cid in first as u32..=(last as u32).min(first as u32 + 0xFFFF) {
+                        self.cid_widths.insert(cid, width);
+                    }
+                    pos += 3;
+                }
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// 字符串中的各字符码及其字节长度
+    fn codes(&self, bytes: &[u8]) -> Vec<(u32, usize)> {
+        let mut codes = vec![];
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let len = match (&self.to_unicode, self.is_type0) {
+                (Some(to_unicode), _) => to_unicode.code_len(&bytes[pos..]),
+                (None, true) => 2.min(bytes.len() - pos),
+                (None, false) => 1,
+            };
+            let code = bytes[pos..pos + len].iter().fold(0, |n, &b| n << 8 | b as u32);
+            codes.push((code, len));
+            pos += len;
+        }
+        codes
+    }
+
+    fn width(&self, code: u32) -> f32 {
+        if self.is_type0 {
+            return self.cid_widths.get(&code).copied().unwrap_or(self.default_width);
+        }
+        usize::try_from(code as i64 - self.first_char).ok()
+            .and_then(|index| self.widths.get(index).copied())
+            .unwrap_or(self.default_width)
+    }
+
+    /// 解码字符串, ToUnicode 优先; 无 ToUnicode 且 CMap 非 Unicode 的 CID 字体无法解码
+    fn decode(&self, bytes: &[u8]) -> Option<String> {
+        match (&self.to_unicode, self.is_type0) {
+            (Some(to_unicode), _) => Some(to_unicode.decode(bytes)),
+            (None, true) if self.unicode_cmap => Some(utf16_be(bytes)),
+            (None, true) => None,
+            (None, false) => Some(self.encoding.decode(bytes)),
+        }
+    }
+}
+
+/// 文本状态
+struct TextState<'a> {
+    ctm: Matrix,
+    stack: Vec<Matrix>,
+    tm: Matrix,
+    tlm: Matrix,
+    font: &'a Font,
+    font_size: f32,
+    char_spacing: f32,
+    word_spacing: f32,
+    // 水平缩放, 1.0 为 100%
+    scale: f32,
+    leading: f32,
+}
+
+impl TextState<'_> {
+    fn move_line(&mut self, tx: f32, ty: f32) {
+        self.tlm = multiply(&[1.0, 0.0, 0.0, 1.0, tx, ty], &self.tlm);
+        self.tm = self.tlm;
+    }
+
+    fn advance(&mut self, tx: f32) {
+        self.tm = multiply(&[1.0, 0.0, 0.0, 1.0, tx, 0.0], &self.tm);
+    }
+
+    /// 绘制字符串, 返回其外框
+    fn show(&mut self, bytes: &[u8]) -> [f32; 4] {
+        let start = multiply(&self.tm, &self.ctm);
+        let mut width = 0.0;
+        for (code, len) in self.font.codes(bytes) {
+            // 字间距只作用于单字节码 32
+            let spacing = if code == 32 && len == 1 { self.word_spacing } else { 0.0 };
+            width += (self.font.width(code) / 1000.0 * self.font_size + self.char_spacing + spacing) * self.scale;
+        }
+        self.advance(width);
+        let (low, high) = (DESCENT * self.font_size, ASCENT * self.font_size);
+        let corners = [(0.0, low), (width, low), (0.0, high), (width, high)].map(|point| transform(&start, point));
+        corners.iter().fold([f32::MAX, f32::MAX, f32::MIN, f32::MIN], |[x0, y0, x1, y1], &(x, y)| {
+            [x0.min(x), y0.min(y), x1.max(x), y1.max(y)]
+        })
+    }
+}
+
+fn collect_text(operations: &[Operation], fonts: &BTreeMap<Vec<u8>, Font>) -> PageText {
+    let default_font = Font::default();
+    let mut page = PageText::default();
+    let mut state = TextState {
+        ctm: IDENTITY,
+        stack: vec![],
+        tm: IDENTITY,
+        tlm: IDENTITY,
+        font: &default_font,
+        font_size: 1.0,
+        char_spacing: 0.0,
+        word_spacing: 0.0,
+        scale: 1.0,
+        leading: 0.0,
+    };
+    let mut line_y: Option<f32> = None;
+    for operation in operations {
+        let operands = &operation.operands;
+        let num = |index: usize| operands.get(index).and_then(number).unwrap_or(0.0);
+        match operation.operator.as_str() {
+            "q" => state.stack.push(state.ctm),
+            "Q" => state.ctm = state.stack.pop().unwrap_or(IDENTITY),
+            "cm" => state.ctm = multiply(&[num(0), num(1), num(2), num(3), num(4), num(5)], &state.ctm),
+            "BT" => {
+                state.tm = IDENTITY;
+                state.tlm = IDENTITY;
+            }
+            "Tf" => {
+                state.font = operands.first().and_then(|name| name.as_name().ok()).and_then(|name| fonts.get(name)).unwrap_or(&default_font);
+                state.font_size = num(1);
+            }
+            "Tc" => state.char_spacing = num(0),
+            "Tw" => state.word_spacing = num(0),
+            "Tz" => state.scale = num(0) / 100.0,
+            "TL" => state.leading = num(0),
+            "Td" | "TD" => {
+                let ty = num(1);
+                if operation.operator == "TD" {
+                    state.leading = -ty;
+                }
+                state.move_line(num(0), ty);
+                push_break(&mut page.text, if ty != 0.0 { '\n' } else { ' ' });
+            }
+            "Tm" => {
+                state.tlm = [num(0), num(1), num(2), num(3), num(4), num(5)];
+                state.tm = state.tlm;
+                let y = operands.get(5).and_then(number);
+                push_break(&mut page.text, if y != line_y { '\n' } else { ' ' });
+                line_y = y;
+            }
+            "T*" => {
+                state.move_line(0.0, -state.leading);
+                push_break(&mut page.text, '\n');
+            }
+            "Tj" => push_string(&mut page, &mut state, operands.first()),
+            "'" | "\"" => {
+                if operation.operator == "\"" {
+                    state.word_spacing = num(0);
+                    state.char_spacing = num(1);
+                }
+                state.move_line(0.0, -state.leading);
+                push_break(&mut page.text, '\n');
+                push_string(&mut page, &mut state, operands.last());
+            }
+            "TJ" => {
+                let Some(Ok(items)) = operands.first().map(Object::as_array) else {
+                    continue;
+                };
+                for item in items {
+                    match number(item) {
+                        Some(offset) => {
+                            state.advance(-offset / 1000.0 * state.font_size * state.scale);
+                            if offset < TJ_SPACE_THRESHOLD {
+                                push_break(&mut page.text, ' ');
+                            }
+                        }
+                        None => push_string(&mut page, &mut state, Some(item)),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let len = page.text.trim_end().len();
+    page.text.truncate(len);
+    page
+}
+
+/// 按显示宽度折行: 在空格后或中日韩文字前后断开, 找不到断点时硬断
+pub fn wrap_lines(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![];
+    for source in text.lines() {
+        let mut line = String::new();
+        let mut line_width = 0;
+        // 行内最后一个断点的字节位置
+        let mut break_at: Option<usize> = None;
+        for c in source.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if is_cjk(c) && !line.is_empty() {
+                break_at = Some(line.len());
+            }
+            if line_width + char_width > width && !line.is_empty() {
+                let at = break_at.filter(|&at| at > 0).unwrap_or(line.len());
+                let rest = line.split_off(at);
+                lines.push(line.trim_end().to_string());
+                line = rest.trim_start().to_string();
+                line_width = line.chars().map(|c| c.width().unwrap_or(0)).sum();
+                break_at = None;
+            }
+            if c == ' ' && line.is_empty() && !lines.is_empty() {
+                continue;
+            }
+            line.push(c);
+            line_width += char_width;
+            if c == ' ' || is_cjk(c) {
+                break_at = Some(line.len());
+            }
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+fn center(span: &TextSpan) -> f32 {
+    (span.bbox[1] + span.bbox[3]) / 2.0
+}
+
+fn height(span: &TextSpan) -> f32 {
+    span.bbox[3] - span.bbox[1]
+}
+
+/// 中日韩文字, 行间合并时不加空格
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3000..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0xFF00..=0xFFEF)
+}
+
+fn number(object: &Object) -> Option<f32> {
+    match *object {
+        Object::Integer(i) => Some(i as f32),
+        Object::Real(f) => Some(f),
+        _ => None,
+    }
+}
+
+fn multiply(m1: &Matrix, m2: &Matrix) -> Matrix {
+    [
+        m1[0] * m2[0] + m1[1] * m2[2],
+        m1[0] * m2[1] + m1[1] * m2[3],
+        m1[2] * m2[0] + m1[3] * m2[2],
+        m1[2] * m2[1] + m1[3] * m2[3],
+        m1[4] * m2[0] + m1[5] * m2[2] + m2[4],
+        m1[4] * m2[1] + m1[5] * m2[3] + m2[5],
+    ]
+}
+
+fn transform(m: &Matrix, (x, y): (f32, f32)) -> (f32, f32) {
+    (x * m[0] + y * m[2] + m[4], x * m[1] + y * m[3] + m[5])
+}
+
+fn push_string(page: &mut PageText, state: &mut TextState, object: Option<&Object>) {
+    let Some(Object::String(bytes, _)) = object else {
+        return;
+    };
+    let bbox = state.show(bytes);
+    if let Some(text) = state.font.decode(bytes) {
+        let start = page.text.len();
+        page.text.push_str(&text);
+        if page.text.len() > start {
+            page.spans.push(TextSpan { range: (start, page.text.len()), bbox });
+        }
+    }
+}
+
+/// 追加空白, 不在开头追加, 不重复追加, 换行覆盖空格
+fn push_break(text: &mut String, c: char) {
+    match text.chars().last() {
+        None | Some('\n') => {}
+        Some(' ') => {
+            if c == '\n' {
+                text.pop();
+                text.push('\n');
+            }
+        }
+        Some(_) => text.push(c),
+    }
+}
+
+impl PageText {
+    /// 按位置重排的文本: 自上而下分行, 行内自左而右, 段内各行合并以便按显示宽度重新折行, 段间空一行
+    pub fn reading_order(&self) -> String {
+        let mut spans: Vec<&TextSpan> = self.spans.iter()
+            .filter(|span| !self.text[span.range.0..span.range.1].trim().is_empty())
+            .collect();
+        spans.sort_by(|a, b| center(b).total_cmp(&center(a)));
+        // 中线相差不到半个字高的算作同一行
+        let mut lines: Vec<Vec<&TextSpan>> = vec![];
+        for span in spans {
+            match lines.last_mut() {
+                Some(line) if (center(line[0]) - center(span)).abs() < height(line[0]).max(height(span)) / 2.0 => line.push(span),
+                _ => lines.push(vec![span]),
+            }
+        }
+        let mut text = String::new();
+        let mut previous: Option<(f32, f32)> = None;
+        for line in lines.iter_mut() {
+            line.sort_by(|a, b| a.bbox[0].total_cmp(&b.bbox[0]));
+            let mut line_text = String::new();
+            let mut right: Option<f32> = None;
+            for span in line.iter() {
+                let s = &self.text[span.range.0..span.range.1];
+                // 间隔超过 0.15 字高补空格
+                if right.is_some_and(|right| span.bbox[0] - right > height(span) * 0.15) {
+                    push_break(&mut line_text, ' ');
+                }
+                line_text.push_str(s);
+                right = Some(span.bbox[2]);
+            }
+            let line_text = line_text.trim();
+            let (y, h) = (center(line[0]), height(line[0]));
+            match previous {
+                None => {}
+                // 行距超过 1.8 倍字高为新段落
+                Some((previous_y, previous_h)) if previous_y - y > previous_h.max(h) * 1.8 => text.push_str("\n\n"),
+                Some(_) => {
+                    if text.ends_with('-') && line_text.starts_with(char::is_lowercase) {
+                        text.pop();
+                    } else if !text.ends_with(is_cjk) && !line_text.starts_with(is_cjk) {
+                        text.push(' ');
+                    }
+                }
+            }
+            text.push_str(line_text);
+            previous = Some((y, h));
+        }
+        text
+    }
+
+    /// 文本字节范围 `range` 在页面上的外框, 每段文字一个, 段内按字符数线性估计
+    pub fn boxes(&self, (start, end): (usize, usize)) -> Vec<[f32; 4]> {
+        self.spans.iter()
+            .filter(|span| span.range.0 < end && start < span.range.1)
+            .map(|span| {
+                let text = &self.text[span.range.0..span.range.1];
+                let total = text.chars().count().max(1) as f32;
+                let offset = |pos: usize| text[..pos.clamp(span.range.0, span.range.1) - span.range.0].chars().count() as f32 / total;
+                let (from, to) = (offset(start), offset(end));
+                let [x0, y0, x1, y1] = span.bbox;
+                [x0 + (x1 - x0) * from, y0, x0 + (x1 - x0) * to, y1]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lopdf::content::Operation;
+    use lopdf::{Object, StringFormat};
+    use super::{collect_text, wrap_lines};
+
+    fn string(s: &str) -> Object {
+        Object::String(s.as_bytes().to_vec(), StringFormat::Literal)
+    }
+
+    #[test]
+    fn lines_and_words() {
+        let operations = vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Td", vec![72.into(), 700.into()]),
+            Operation::new("Tj", vec![string("Hello")]),
+            Operation::new("TJ", vec![Object::Array(vec![string("wor"), (-20).into(), string("ld"), (-400).into(), string("again")])]),
+            Operation::new("Td", vec![0.into(), (-14).into()]),
+            Operation::new("Tj", vec![string("next line")]),
+            Operation::new("ET", vec![]),
+        ];
+        assert_eq!(collect_text(&operations, &Default::default()).text, "Helloworld again\nnext line");
+    }
+
+    #[test]
+    fn span_positions() {
+        let operations = vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec![Object::Name(b"F1".to_vec()), 10.into()]),
+            Operation::new("Tm", vec![2.into(), 0.into(), 0.into(), 2.into(), 100.into(), 500.into()]),
+            Operation::new("Tj", vec![string("abcd")]),
+            Operation::new("TJ", vec![Object::Array(vec![(-1000).into(), string("ef")])]),
+            Operation::new("ET", vec![]),
+        ];
+        let page = collect_text(&operations, &Default::default());
+        assert_eq!(page.text, "abcd ef");
+        // 默认字宽 0.5 字号, 文本矩阵放大 2 倍
+        assert_eq!(page.spans[0].bbox, [100.0, 496.0, 140.0, 516.0]);
+        assert_eq!(page.spans[1].bbox, [160.0, 496.0, 180.0, 516.0]);
+        assert_eq!(page.boxes((2, 6)), vec![[120.0, 496.0, 140.0, 516.0], [160.0, 496.0, 170.0, 516.0]]);
+    }
+
+    #[test]
+    fn wrap_by_width() {
+        assert_eq!(wrap_lines("the quick brown fox\n\nabcdefghij", 10), vec!["the quick", "brown fox", "", "abcdefghij"]);
+        assert_eq!(wrap_lines("abcdefghijkl", 5), vec!["abcde", "fghij", "kl"]);
+        // 中文每字宽 2
+        assert_eq!(wrap_lines("中文文本折行", 5), vec!["中文", "文本", "折行"]);
+        assert_eq!(wrap_lines("see 中文", 6), vec!["see 中", "文"]);
+    }
+
+    #[test]
+    fn reading_order_from_positions() {
+        // 第二行先于第一行写出, 第一行两段倒序写出
+        let operations = vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec![Object::Name(b"F1".to_vec()), 10.into()]),
+            Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 72.into(), 688.into()]),
+            Operation::new("Tj", vec![string("ple line")]),
+            Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 102.into(), 700.into()]),
+            Operation::new("Tj", vec![string("exam-")]),
+            Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 72.into(), 700.into()]),
+            Operation::new("Tj", vec![string("an")]),
+            Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 72.into(), 600.into()]),
+            Operation::new("Tj", vec![string("next")]),
+            Operation::new("ET", vec![]),
+        ];
+        let page = collect_text(&operations, &Default::default());
+        assert_eq!(page.reading_order(), "an example line\n\nnext");
+    }
+}
@@ -1,5 +1,5 @@
 use crate::app::{App};
-use crate::event::EventHandler;
+use crate::image::{self, GraphicsProtocol, TerminalGraphics};
 use crate::ui;
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
@@ -7,6 +7,10 @@ use ratatui::backend::Backend;
 use ratatui::Terminal;
 use std::io;
 use std::panic;
+use std::time::Duration;
+
+/// How long to wait for the terminal to answer the graphics queries
+const DETECT_TIMEOUT: Duration = Duration::from_millis(500);
 
 /// Representation of a terminal user interface.
 ///
@@ -16,21 +20,24 @@ use std::panic;
 pub struct Tui<B: Backend> {
     /// Interface to the Terminal.
     terminal: Terminal<B>,
-    /// Terminal event handler.
-    pub events: EventHandler,
 }
 
 impl<B: Backend> Tui<B> {
     /// Constructs a new instance of [`Tui`].
-    pub fn new(terminal: Terminal<B>, events: EventHandler) -> Self {
-        Self { terminal, events }
+    pub fn new(terminal: Terminal<B>) -> Self {
+        Self { terminal }
     }
 
     /// Initializes the terminal interface.
     ///
-    /// It enables the raw mode and sets terminal properties.
-    pub fn init(&mut self) -> anyhow::Result<()> {
+    /// It enables the raw mode, sets terminal properties and returns the
+    /// graphics protocol to draw pages with: `graphics` if given, otherwise
+    /// the one probed from the terminal, along with the cell size. The probe
+    /// reads the tty, so this must run before the
+    /// [`EventHandler`](crate::event::EventHandler) is created.
+    pub fn init(&mut self, graphics: Option<GraphicsProtocol>) -> anyhow::Result<TerminalGraphics> {
         terminal::enable_raw_mode()?;
+        let graphics = image::detect(graphics, DETECT_TIMEOUT);
         crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
 
         // Define a custom panic hook to reset the terminal properties.
@@ -43,7 +50,7 @@ impl<B: Backend> Tui<B> {
 
         self.terminal.hide_cursor()?;
         self.terminal.clear()?;
-        Ok(())
+        Ok(graphics)
     }
 
     /// [`Draw`] the terminal interface by [`rendering`] the widgets.
@@ -55,6 +62,12 @@ impl<B: Backend> Tui<B> {
         Ok(())
     }
 
+    /// Clears the screen so the next draw repaints every cell.
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     /// Resets the terminal interface.
     ///
     /// This function is also used for the panic hook to revert
@@ -4,11 +4,12 @@ use ratatui::{
     Frame,
 };
 use ratatui::prelude::*;
-use ratatui::widgets::{Borders, List, ListItem, Paragraph};
+use ratatui::widgets::{Borders, Clear, List, ListItem, Paragraph, Wrap};
 
 use crate::app::{App, AppState};
 use crate::emit;
-use crate::pdf::{BookMarkIndex, BookMarkType};
+use crate::pdf::{BookMarkIndex, BookMarkType, ZoomMode};
+use crate::text::wrap_lines;
 
 /// Renders the user interface widgets.
 pub fn render(app: &mut App, frame: &mut Frame) {
@@ -17,18 +18,26 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(20), Constraint::Fill(1)])
         .split(frame.size());
-    match &app.app_state {
+    let sidebar = match &app.app_state {
         AppState::Search(text) => {
             let chunk = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Length(3), Constraint::Fill(1)])
                 .split(chunk[0]);
-            render_search_box(frame, chunk[0], text);
-            render_catalog(app, frame, chunk[1]);
-        }
-        _ => {
-            render_catalog(app, frame, chunk[0]);
+            render_search_box(frame, chunk[0], text, app.search_regex, app.search_error.as_deref());
+            chunk[1]
         }
+        _ => chunk[0],
+    };
+    if app.search_results.is_empty() {
+        render_catalog(app, frame, sidebar);
+    } else {
+        let sidebar = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Fill(1), Constraint::Percentage(40)])
+            .split(sidebar);
+        render_catalog(app, frame, sidebar[0]);
+        render_search_results(app, frame, sidebar[1]);
     }
 
     // right side => pdf preview
@@ -37,23 +46,100 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         .constraints([Constraint::Length(2), Constraint::Fill(1)])
         .split(chunk[1]);
 
-    render_pdf(app, chunk[1]);
+    // 输入密码前没有可显示的页面
+    match &app.app_state {
+        AppState::Password(password) => render_password(frame, chunk[1], password),
+        _ => render_pdf(app, frame, chunk[1]),
+    }
 
     let page_id =
-        if let AppState::JumpPage(page_id) = &app.app_state { Some(page_id.parse::<u32>().unwrap_or(0)) } else { None };
+        if let AppState::JumpPage(page_id) = &app.app_state { Some(page_id.clone()) } else { None };
     render_title(app, frame, chunk[0], page_id);
+
+    if let AppState::Metadata = app.app_state {
+        render_metadata(app, frame, frame.size());
+    }
+}
+
+fn render_password(frame: &mut Frame, area: Rect, password: &str) {
+    let width = 40.min(area.width);
+    let height = 3.min(area.height);
+    let popup = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+    let paragraph = Paragraph::new(Line::from(Span::styled("*".repeat(password.chars().count()), Style::default().green())))
+        .block(Block::default().title("输入密码").borders(Borders::ALL).border_style(Style::new().blue()));
+    frame.render_widget(paragraph, popup);
+}
+
+fn render_metadata(app: &App, frame: &mut Frame, area: Rect) {
+    let rows = app.pdf_handler.get_metadata().rows();
+    let lines: Vec<Line> = rows.into_iter().map(|(label, value)| Line::from(vec![
+        Span::styled(format!("{label}: "), Style::default().yellow()),
+        Span::raw(value),
+    ])).collect();
+    let width = (area.width * 3 / 5).max(40).min(area.width);
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    // 长的值会折行, 按边框内的宽度算行数
+    let line_count = paragraph.line_count(width.saturating_sub(2)).min(u16::MAX as usize) as u16;
+    let height = line_count.saturating_add(2).min(area.height);
+    let popup = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+    let paragraph = paragraph
+        .block(Block::default().title("文档信息").borders(Borders::ALL).border_style(Style::new().blue()));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(paragraph, popup);
 }
 
-fn render_search_box(frame: &mut Frame, chunk: Rect, text: &String) {
+fn render_search_box(frame: &mut Frame, chunk: Rect, text: &String, is_regex: bool, error: Option<&str>) {
+    let block = Block::default().borders(Borders::ALL);
+    let block = match error {
+        Some(error) => block.title(error).border_style(Style::new().red()),
+        None if is_regex => block.title("正则").border_style(Style::new().blue()),
+        None => block.border_style(Style::new().blue()),
+    };
     let paragraph = Paragraph::new(Line::from(Span::styled(
-        format!("{text}"),
+        text.to_string(),
         Style::default().green(),
-    ))).block(Block::default().borders(Borders::ALL).border_style(Style::new().blue()));
+    ))).block(block);
     frame.render_widget(paragraph, chunk);
 }
 
-fn render_title(app: &mut App, frame: &mut Frame, chunk: Rect, page_id: Option<u32>) {
-    let loading = if app.loading { String::from("加载中...") } else { String::new() };
+fn render_search_results(app: &mut App, frame: &mut Frame, chunk: Rect) {
+    let items: Vec<ListItem> = app.search_results.iter().map(|hit| {
+        let (start, end) = hit.snippet_range;
+        ListItem::new(Line::from(vec![
+            Span::styled(format!("{:>4} ", hit.page), Style::default().blue()),
+            Span::raw(&hit.snippet[..start]),
+            Span::styled(&hit.snippet[start..end], Style::default().red()),
+            Span::raw(&hit.snippet[end..]),
+        ]))
+    }).collect();
+    let title = format!("搜索结果 {}", app.search_results.len());
+    let list_widget = List::new(items)
+        .block(Block::default().title(title).borders(Borders::TOP | Borders::RIGHT))
+        .highlight_style(Style::new().italic().on_dark_gray())
+        .highlight_symbol("*");
+    frame.render_stateful_widget(list_widget, chunk, &mut app.search_state);
+}
+
+fn render_title(app: &mut App, frame: &mut Frame, chunk: Rect, page_id: Option<String>) {
+    let loading = match &app.status {
+        Some(status) => status.clone(),
+        None if app.loading && !app.text_mode => String::from("加载中..."),
+        None => String::new(),
+    };
+    let zoom = match app.pdf_size.zoom() {
+        _ if app.text_mode => String::from("文本"),
+        ZoomMode::Fixed(percent) => format!("{percent}%"),
+        zoom => format!("{zoom} {}%", app.pdf_size.percent(app.cur_page_size())),
+    };
+    let pages = match (&app.app_state, app.right_page()) {
+        (AppState::Grid(selected), _) => vec![*selected],
+        (_, Some(right)) => vec![app.cur_page, right],
+        (_, None) => vec![app.reading_page()],
+    };
+    // 页码标签与页数不同时附在后面
+    let labels: Vec<&str> = pages.iter().filter_map(|&page| app.pdf_handler.get_page_label(page)).collect();
+    let labels = if labels.is_empty() { String::new() } else { format!(" ({})", labels.join("-")) };
+    let pages = pages.iter().map(u32::to_string).collect::<Vec<_>>().join("-");
     let mut line = Vec::new();
     if let Some(page_id) = page_id {
         line.push(Span::styled(
@@ -61,16 +147,16 @@ fn render_title(app: &mut App, frame: &mut Frame, chunk: Rect, page_id: Option<u
             Style::default().green(),
         ));
         line.push(Span::styled(
-            format!("{page_id}"),
+            page_id,
             Style::default().red(),
         ));
         line.push(Span::styled(
-            format!("/{} 页  {loading}", app.pdf_handler.get_page_nums()),
+            format!("/{} 页  {zoom}  {loading}", app.pdf_handler.get_page_nums()),
             Style::default().green(),
         ))
     } else {
         line.push(Span::styled(
-            format!("第 {}/{} 页  {loading}", app.cur_page, app.pdf_handler.get_page_nums()),
+            format!("第 {}/{} 页{labels}  {zoom}  {loading}", pages, app.pdf_handler.get_page_nums()),
             Style::default().green(),
         ));
     }
@@ -79,13 +165,36 @@ fn render_title(app: &mut App, frame: &mut Frame, chunk: Rect, page_id: Option<u
     frame.render_widget(title, chunk);
 }
 
-fn render_pdf(app: &mut App, chunk: Rect) {
+fn render_pdf(app: &mut App, frame: &mut Frame, chunk: Rect) {
+    if app.text_mode {
+        return render_page_text(app, frame, chunk);
+    }
     if !app.already_render {
         app.already_render = true;
         app.loading = true;
-        app.pdf_size.update(&chunk);
+        app.pdf_size.update(&chunk, app.image_handler.get_cell_size());
+        app.update_render_size();
         emit!(LoadingFirst(app.cur_page));
     }
+    if let Some(widget) = app.image_handler.unicode_widget() {
+        frame.render_widget(widget, chunk);
+    }
+}
+
+fn render_page_text(app: &mut App, frame: &mut Frame, chunk: Rect) {
+    app.text_lines = wrap_lines(app.reading_text(app.cur_page), chunk.width as usize);
+    app.text_height = chunk.height;
+    let lines = app.text_lines.len().min(u16::MAX as usize) as u16;
+    app.text_scroll = app.text_scroll.min(lines.saturating_sub(chunk.height));
+    let selected = app.text_selection.map(|(anchor, cursor)| anchor.min(cursor)..=anchor.max(cursor));
+    let lines: Vec<Line> = app.text_lines.iter().enumerate().map(|(index, line)| {
+        match &selected {
+            Some(selected) if selected.contains(&index) => Line::styled(line.as_str(), Style::new().reversed()),
+            _ => Line::raw(line.as_str()),
+        }
+    }).collect();
+    let paragraph = Paragraph::new(lines).scroll((app.text_scroll, 0));
+    frame.render_widget(paragraph, chunk);
 }
 
 fn render_catalog(app: &mut App, frame: &mut Frame, chunk: Rect) {
@@ -142,5 +251,5 @@ fn parse_book_marks_item(book_marks: &Vec<BookMarkType>, items: &mut Vec<ListIte
         index += 1;
         cur_index.pop();
     }
-    return result;
+    result
 }
\ No newline at end of file